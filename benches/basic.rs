@@ -2,10 +2,11 @@
 
 extern crate test;
 
-use calamine::{open_workbook, Ods, Reader, Xls, Xlsb, Xlsx};
+use calamine::{open_workbook, Ods, Reader, Xls, Xlsb, Xlsx, XlsxOptions};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Cursor, Write};
 use test::Bencher;
+use zip::write::{FileOptions, ZipWriter};
 
 fn count<R: Reader<RS = BufReader<File>>>(path: &str) -> usize {
     let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path);
@@ -35,6 +36,152 @@ fn bench_xlsx(b: &mut Bencher) {
     b.iter(|| count::<Xlsx<_>>("tests/issues.xlsx"));
 }
 
+fn count_xlsx_with_capacity(path: &str, capacity: usize) -> usize {
+    let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path);
+    let mut excel = Xlsx::open_with_options(&path, XlsxOptions::new().buffer_capacity(capacity))
+        .expect("cannot open excel file");
+    let range = excel.worksheet_range_at(0).unwrap().unwrap();
+    range.rows().flat_map(|r| r.iter()).count()
+}
+
+#[bench]
+fn bench_xlsx_large_default_buffer(b: &mut Bencher) {
+    b.iter(|| count_xlsx_with_capacity("tests/issue_174.xlsx", 8 * 1024));
+}
+
+#[bench]
+fn bench_xlsx_large_64k_buffer(b: &mut Bencher) {
+    b.iter(|| count_xlsx_with_capacity("tests/issue_174.xlsx", 64 * 1024));
+}
+
+/// Kind of cells of the generated worksheets
+#[derive(Clone, Copy)]
+enum Cells {
+    /// numbers only
+    Numbers,
+    /// shared strings only, 1000 distinct strings
+    Strings,
+    /// numbers, only 1 cell every 10 rows and 10 columns
+    Sparse,
+}
+
+/// Builds an in memory xlsx with a single `rows` x `cols` sheet
+fn generated_xlsx(rows: u32, cols: u32, dimension: bool, cells: Cells) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default();
+    let mut add = |path: &str, content: &[u8]| {
+        zip.start_file(path, options).unwrap();
+        zip.write_all(content).unwrap();
+    };
+    add(
+        "xl/workbook.xml",
+        br#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+    );
+    add(
+        "xl/_rels/workbook.xml.rels",
+        br#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+    );
+    if let Cells::Strings = cells {
+        let mut sst = String::from(
+            r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+        );
+        for i in 0..1000 {
+            sst.push_str(&format!("<si><t>string number {}</t></si>", i));
+        }
+        sst.push_str("</sst>");
+        add("xl/sharedStrings.xml", sst.as_bytes());
+    }
+    let mut sheet = String::from(
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    );
+    if dimension {
+        sheet.push_str(&format!(
+            r#"<dimension ref="A1:{}{}"/>"#,
+            col_name(cols - 1),
+            rows
+        ));
+    }
+    sheet.push_str("<sheetData>");
+    for r in 1..=rows {
+        if let Cells::Sparse = cells {
+            if r % 10 != 1 {
+                continue;
+            }
+        }
+        sheet.push_str(&format!(r#"<row r="{}" spans="1:{}">"#, r, cols));
+        for c in 0..cols {
+            match cells {
+                Cells::Numbers => {
+                    sheet.push_str(&format!(r#"<c r="{}{}"><v>{}</v></c>"#, col_name(c), r, c))
+                }
+                Cells::Strings => sheet.push_str(&format!(
+                    r#"<c r="{}{}" t="s"><v>{}</v></c>"#,
+                    col_name(c),
+                    r,
+                    (r * cols + c) % 1000
+                )),
+                Cells::Sparse if c % 10 == 0 => {
+                    sheet.push_str(&format!(r#"<c r="{}{}"><v>{}</v></c>"#, col_name(c), r, c))
+                }
+                Cells::Sparse => (),
+            }
+        }
+        sheet.push_str("</row>");
+    }
+    sheet.push_str("</sheetData></worksheet>");
+    add("xl/worksheets/sheet1.xml", sheet.as_bytes());
+    zip.finish().unwrap().into_inner()
+}
+
+fn col_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (col % 26) as u8);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+fn count_xlsx_in_memory(data: &[u8]) -> usize {
+    let mut excel = Xlsx::new(Cursor::new(data)).expect("cannot open excel file");
+    let range = excel.worksheet_range_at(0).unwrap().unwrap();
+    range.rows().flat_map(|r| r.iter()).count()
+}
+
+#[bench]
+fn bench_xlsx_1m_cells(b: &mut Bencher) {
+    let data = generated_xlsx(1000, 1000, true, Cells::Numbers);
+    b.iter(|| count_xlsx_in_memory(&data));
+}
+
+#[bench]
+fn bench_xlsx_1m_cells_no_dimension(b: &mut Bencher) {
+    let data = generated_xlsx(1000, 1000, false, Cells::Numbers);
+    b.iter(|| count_xlsx_in_memory(&data));
+}
+
+#[bench]
+fn bench_xlsx_numbers(b: &mut Bencher) {
+    let data = generated_xlsx(1000, 50, true, Cells::Numbers);
+    b.iter(|| count_xlsx_in_memory(&data));
+}
+
+#[bench]
+fn bench_xlsx_shared_strings(b: &mut Bencher) {
+    let data = generated_xlsx(1000, 50, true, Cells::Strings);
+    b.iter(|| count_xlsx_in_memory(&data));
+}
+
+#[bench]
+fn bench_xlsx_sparse(b: &mut Bencher) {
+    let data = generated_xlsx(5000, 500, true, Cells::Sparse);
+    b.iter(|| count_xlsx_in_memory(&data));
+}
+
 #[bench]
 fn bench_xlsb(b: &mut Bencher) {
     b.iter(|| count::<Xlsb<_>>("tests/issues.xlsb"));
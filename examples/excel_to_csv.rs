@@ -1,4 +1,4 @@
-use calamine::{open_workbook_auto, DataType, Range, Reader};
+use calamine::{open_workbook_auto, Reader};
 use std::env;
 use std::fs::File;
 use std::io::{BufWriter, Write};
@@ -26,26 +26,6 @@ fn main() {
     let mut xl = open_workbook_auto(&sce).unwrap();
     let range = xl.worksheet_range(&sheet).unwrap().unwrap();
 
-    write_range(&mut dest, &range).unwrap();
-}
-
-fn write_range<W: Write>(dest: &mut W, range: &Range<DataType>) -> std::io::Result<()> {
-    let n = range.get_size().1 - 1;
-    for r in range.rows() {
-        for (i, c) in r.iter().enumerate() {
-            match *c {
-                DataType::Empty => Ok(()),
-                DataType::String(ref s) => write!(dest, "{}", s),
-                DataType::Float(ref f) | DataType::DateTime(ref f) => write!(dest, "{}", f),
-                DataType::Int(ref i) => write!(dest, "{}", i),
-                DataType::Error(ref e) => write!(dest, "{:?}", e),
-                DataType::Bool(ref b) => write!(dest, "{}", b),
-            }?;
-            if i != n {
-                write!(dest, ";")?;
-            }
-        }
-        write!(dest, "\r\n")?;
-    }
-    Ok(())
+    range.write_csv(&mut dest).unwrap();
+    dest.flush().unwrap();
 }
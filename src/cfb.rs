@@ -3,7 +3,7 @@
 use std::borrow::Cow;
 use std::cmp::min;
 use std::convert::TryInto;
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
 
 use log::debug;
 
@@ -16,6 +16,9 @@ const DIFSECT: u32 = 0xFFFF_FFFC;
 // const FATSECT: u32 = 0xFFFF_FFFD;
 const ENDOFCHAIN: u32 = 0xFFFF_FFFE;
 //const FREESECT: u32 = 0xFFFF_FFFF;
+const STORAGE: u8 = 1;
+const STREAM: u8 = 2;
+const MINI_SECTOR_SIZE: usize = 64;
 
 /// A Cfb specific error enum
 #[derive(Debug)]
@@ -31,6 +34,7 @@ pub enum CfbError {
         found: u16,
     },
     CodePageNotFound(u16),
+    LoopingChain(&'static str),
 }
 
 impl std::fmt::Display for CfbError {
@@ -50,6 +54,7 @@ impl std::fmt::Display for CfbError {
                 name, expected, found
             ),
             CfbError::CodePageNotFound(e) => write!(f, "Codepage {:X} not found", e),
+            CfbError::LoopingChain(e) => write!(f, "Looping {} chain", e),
         }
     }
 }
@@ -77,17 +82,31 @@ impl Cfb {
     /// Create a new `Cfb`
     ///
     /// Starts reading project metadata (header, directories, sectors and minisectors).
+    ///
+    /// `len` is the total length of the container, header included. Sectors are read
+    /// sequentially, on demand, and only up to the last sector needed so far; they are then
+    /// kept in memory for the following `get_stream` calls.
     pub fn new<R: Read>(mut reader: &mut R, len: usize) -> Result<Cfb, CfbError> {
         // load header
         let (h, mut difat) = Header::from_reader(&mut reader)?;
-        let mut sectors = Sectors::new(h.sector_size, Vec::with_capacity(len));
+        // the header fills the first sector (512 or 4096 bytes), sectors hold the rest
+        let mut sectors = Sectors::new(
+            h.sector_size,
+            Vec::with_capacity(len.saturating_sub(h.sector_size)),
+        );
 
         // load fat and dif sectors
         debug!("load difat");
         let mut sector_id = h.difat_start;
+        let mut difat_sectors = 0;
         while sector_id < RESERVED_SECTORS {
+            // each sector is read at most once, a longer chain is necessarily looping
+            difat_sectors += 1;
+            if difat_sectors > len / h.sector_size + 1 {
+                return Err(CfbError::LoopingChain("difat"));
+            }
             difat.extend(to_u32(sectors.get(sector_id, reader)?));
-            sector_id = difat.pop().unwrap(); //TODO: check if in infinite loop
+            sector_id = difat.pop().ok_or_else(unexpected_eof)?;
         }
 
         // load the FATs
@@ -101,7 +120,7 @@ impl Cfb {
         debug!("load directories");
         let dirs = sectors.get_chain(h.dir_start, &fats, reader, h.dir_len * h.sector_size)?;
         let dirs = dirs
-            .chunks(128)
+            .chunks_exact(128)
             .map(|c| Directory::from_slice(c, h.sector_size))
             .collect::<Vec<_>>();
 
@@ -112,7 +131,11 @@ impl Cfb {
 
         // load the mini streams
         debug!("load minis");
-        let ministream = sectors.get_chain(dirs[0].start, &fats, reader, dirs[0].len)?;
+        let mut ministream = sectors.get_chain(dirs[0].start, &fats, reader, dirs[0].len)?;
+        // the root size is not necessarily a multiple of the mini sector size: pad the last
+        // mini sector, streams are truncated to their own size when read
+        let mini_sectors = (ministream.len() + MINI_SECTOR_SIZE - 1) / MINI_SECTOR_SIZE;
+        ministream.resize(mini_sectors * MINI_SECTOR_SIZE, 0);
         let minifat = sectors.get_chain(
             h.mini_fat_start,
             &fats,
@@ -124,7 +147,7 @@ impl Cfb {
             directories: dirs,
             sectors,
             fats,
-            mini_sectors: Sectors::new(64, ministream),
+            mini_sectors: Sectors::new(MINI_SECTOR_SIZE, ministream),
             mini_fats: minifat,
         })
     }
@@ -134,21 +157,114 @@ impl Cfb {
         self.directories.iter().any(|d| &*d.name == name)
     }
 
+    /// Gets the names of all directory entries (streams and storages)
+    ///
+    /// The root entry and unused entries are skipped.
+    pub fn directory_names(&self) -> Vec<String> {
+        self.directories
+            .iter()
+            .skip(1)
+            .filter(|d| !d.name.is_empty())
+            .map(|d| d.name.clone())
+            .collect()
+    }
+
+    /// Checks if a storage (e.g. the storage of a UserForm) exists
+    pub fn has_storage(&self, name: &str) -> bool {
+        self.directories
+            .iter()
+            .any(|d| d.typ == STORAGE && &*d.name == name)
+    }
+
     /// Gets a stream by name out of directories
     pub fn get_stream<R: Read>(&mut self, name: &str, r: &mut R) -> Result<Vec<u8>, CfbError> {
         match self.directories.iter().find(|d| &*d.name == name) {
             None => Err(CfbError::StreamNotFound(name.to_string())),
             Some(d) => {
-                if d.len < 4096 {
-                    // TODO: Study the possibility to return a `VecArray` (stack allocated)
-                    self.mini_sectors
-                        .get_chain(d.start, &self.mini_fats, r, d.len)
-                } else {
-                    self.sectors.get_chain(d.start, &self.fats, r, d.len)
-                }
+                let (start, len) = (d.start, d.len);
+                self.read_stream(start, len, r)
+            }
+        }
+    }
+
+    /// Gets a stream by name out of the direct children of a storage
+    ///
+    /// Unlike `get_stream`, streams with the same name in other storages (e.g. the `f` and
+    /// `o` streams of every UserForm) are ignored.
+    pub fn get_storage_stream<R: Read>(
+        &mut self,
+        storage: &str,
+        name: &str,
+        r: &mut R,
+    ) -> Result<Vec<u8>, CfbError> {
+        let not_found = || CfbError::StreamNotFound(format!("{}/{}", storage, name));
+        let storage = self
+            .directories
+            .iter()
+            .find(|d| d.typ == STORAGE && &*d.name == storage)
+            .ok_or_else(not_found)?;
+        // the children of a storage are a tree linked through their left and right siblings
+        let mut ids = vec![storage.child];
+        let mut visited = 0;
+        while let Some(id) = ids.pop() {
+            // unused links (0xFFFF_FFFF) are out of bounds
+            let d = match self.directories.get(id as usize) {
+                Some(d) => d,
+                None => continue,
+            };
+            visited += 1;
+            if visited > self.directories.len() {
+                return Err(CfbError::LoopingChain("directory"));
+            }
+            if d.typ == STREAM && &*d.name == name {
+                let (start, len) = (d.start, d.len);
+                return self.read_stream(start, len, r);
             }
+            ids.push(d.left);
+            ids.push(d.right);
         }
+        Err(not_found())
     }
+
+    /// Reads a stream out of its first sector and its length
+    fn read_stream<R: Read>(
+        &mut self,
+        start: u32,
+        len: usize,
+        r: &mut R,
+    ) -> Result<Vec<u8>, CfbError> {
+        if len < 4096 {
+            // TODO: Study the possibility to return a `VecArray` (stack allocated)
+            self.mini_sectors.get_chain(start, &self.mini_fats, r, len)
+        } else {
+            self.sectors.get_chain(start, &self.fats, r, len)
+        }
+    }
+}
+
+fn unexpected_eof() -> CfbError {
+    CfbError::Io(std::io::ErrorKind::UnexpectedEof.into())
+}
+
+/// Checks if `r` is a Compound File Binary holding an encrypted (password protected)
+/// Office Open XML package
+///
+/// The reader is rewound to its initial position before returning.
+pub fn is_encrypted_package<R: Read + Seek>(r: &mut R) -> Result<bool, std::io::Error> {
+    let start = r.seek(SeekFrom::Current(0))?;
+    let mut signature = [0u8; 8];
+    let is_ole = r.read_exact(&mut signature).is_ok()
+        && u64::from_le_bytes(signature) == 0xE11A_B1A1_E011_CFD0;
+    let mut is_encrypted = false;
+    if is_ole {
+        let len = r.seek(SeekFrom::End(0))? - start;
+        r.seek(SeekFrom::Start(start))?;
+        if let Ok(cfb) = Cfb::new(r, len as usize) {
+            is_encrypted = cfb.has_directory("EncryptedPackage");
+        }
+    }
+    r.seek(SeekFrom::Start(start))?;
+    Ok(is_encrypted)
 }
 
 /// A hidden struct which defines cfb files structure
@@ -211,9 +327,10 @@ impl Header {
         let mini_fat_start = read_u32(&buf[60..64]);
         let mini_fat_len = read_usize(&buf[64..68]);
         let difat_start = read_u32(&buf[68..72]);
-        let difat_len = read_usize(&buf[62..76]);
+        let difat_len = read_usize(&buf[72..76]);
 
-        let mut difat = Vec::with_capacity(difat_len);
+        // the header only holds 109 difat entries, the other ones are read from sectors
+        let mut difat = Vec::with_capacity(109 + min(difat_len, 1024) * (sector_size / 4));
         difat.extend(to_u32(&buf[76..512]));
 
         Ok((
@@ -250,15 +367,15 @@ impl Sectors {
         let start = id as usize * self.size;
         let end = start + self.size;
         if end > self.data.len() {
-            let mut len = self.data.len();
-            self.data.resize(end, 0);
-            // read_exact or stop if EOF
-            while len < end {
-                let read = r.read(&mut self.data[len..end]).map_err(CfbError::Io)?;
-                if read == 0 {
-                    return Ok(&self.data[start..len]);
-                }
-                len += read;
+            // read_exact or stop if EOF, `read_to_end` grows the buffer as data is read
+            // so an invalid sector id cannot trigger a huge allocation
+            let missing = (end - self.data.len()) as u64;
+            r.by_ref()
+                .take(missing)
+                .read_to_end(&mut self.data)
+                .map_err(CfbError::Io)?;
+            if self.data.len() < end {
+                return Ok(&self.data[min(start, self.data.len())..]);
             }
         }
         Ok(&self.data[start..end])
@@ -271,14 +388,17 @@ impl Sectors {
         r: &mut R,
         len: usize,
     ) -> Result<Vec<u8>, CfbError> {
-        let mut chain = if len > 0 {
-            Vec::with_capacity(len)
-        } else {
-            Vec::new()
-        };
+        // `len` is read from the file, do not trust it blindly
+        let mut chain = Vec::with_capacity(min(len, fats.len() * self.size));
+        let mut sectors = 0;
         while sector_id != ENDOFCHAIN {
+            // each sector is read at most once, a longer chain is necessarily looping
+            sectors += 1;
+            if sectors > fats.len() {
+                return Err(CfbError::LoopingChain("sector"));
+            }
             chain.extend_from_slice(self.get(sector_id, r)?);
-            sector_id = fats[sector_id as usize];
+            sector_id = *fats.get(sector_id as usize).ok_or_else(unexpected_eof)?;
         }
         if len > 0 {
             chain.truncate(len);
@@ -291,6 +411,12 @@ impl Sectors {
 #[derive(Debug, Clone)]
 struct Directory {
     name: String,
+    /// Object type (storage, stream or root storage)
+    typ: u8,
+    /// Left sibling, right sibling and first child ids, used to walk the storages
+    left: u32,
+    right: u32,
+    child: u32,
     start: u32,
     len: usize,
 }
@@ -301,14 +427,30 @@ impl Directory {
         if let Some(l) = name.as_bytes().iter().position(|b| *b == 0) {
             name.truncate(l);
         }
+        let typ = buf[66];
+        let left = read_u32(&buf[68..72]);
+        let right = read_u32(&buf[72..76]);
+        let child = read_u32(&buf[76..80]);
         let start = read_u32(&buf[116..120]);
         let len: usize = if sector_size == 512 {
-            read_u32(&buf[120..124]).try_into().unwrap()
+            read_u32(&buf[120..124])
+                .try_into()
+                .unwrap_or(std::usize::MAX)
         } else {
-            read_u64(&buf[120..128]).try_into().unwrap()
+            read_u64(&buf[120..128])
+                .try_into()
+                .unwrap_or(std::usize::MAX)
         };
 
-        Directory { start, len, name }
+        Directory {
+            name,
+            typ,
+            left,
+            right,
+            child,
+            start,
+            len,
+        }
     }
 }
 
@@ -336,17 +478,21 @@ pub fn decompress_stream(s: &[u8]) -> Result<Vec<u8>, CfbError> {
     debug!("decompress stream");
     let mut res = Vec::new();
 
-    if s[0] != 0x01 {
-        return Err(CfbError::Invalid {
-            name: "signature",
-            expected: "0x01",
-            found: s[0] as u16,
-        });
+    match s.first() {
+        Some(0x01) => (),
+        Some(s) => {
+            return Err(CfbError::Invalid {
+                name: "signature",
+                expected: "0x01",
+                found: *s as u16,
+            })
+        }
+        None => return Err(unexpected_eof()),
     }
 
     let mut i = 1;
     while i < s.len() {
-        let chunk_header = read_u16(&s[i..]);
+        let chunk_header = read_u16(s.get(i..i + 2).ok_or_else(unexpected_eof)?);
         i += 2;
 
         // each 'chunk' is 4096 wide, let's reserve that space
@@ -357,11 +503,17 @@ pub fn decompress_stream(s: &[u8]) -> Result<Vec<u8>, CfbError> {
         let chunk_signature = (chunk_header & 0x7000) >> 12;
         let chunk_flag = (chunk_header & 0x8000) >> 15;
 
-        assert_eq!(chunk_signature, 0b011, "i={}, len={}", i, s.len());
+        if chunk_signature != 0b011 {
+            return Err(CfbError::Invalid {
+                name: "chunk signature",
+                expected: "0b011",
+                found: chunk_signature,
+            });
+        }
 
         if chunk_flag == 0 {
             // uncompressed
-            res.extend_from_slice(&s[i..i + 4096]);
+            res.extend_from_slice(s.get(i..i + 4096).ok_or_else(unexpected_eof)?);
             i += 4096;
         } else {
             let mut chunk_len = 0;
@@ -382,20 +534,33 @@ pub fn decompress_stream(s: &[u8]) -> Result<Vec<u8>, CfbError> {
 
                     if (bit_flags & (1 << bit_index)) == 0 {
                         // literal token
-                        res.push(s[i]);
+                        res.push(*s.get(i).ok_or_else(unexpected_eof)?);
                         i += 1;
                         chunk_len += 1;
                     } else {
                         // copy token
-                        let token = read_u16(&s[i..]);
+                        let token = read_u16(s.get(i..i + 2).ok_or_else(unexpected_eof)?);
                         i += 2;
                         chunk_len += 2;
 
                         let decomp_len = res.len() - start;
-                        let bit_count = (4..16).find(|i| POWER_2[*i] >= decomp_len).unwrap();
+                        let bit_count = (4..16).find(|i| POWER_2[*i] >= decomp_len).ok_or(
+                            CfbError::Invalid {
+                                name: "decompressed chunk length",
+                                expected: "at most 4096",
+                                found: min(decomp_len, 0xFFFF) as u16,
+                            },
+                        )?;
                         let len_mask = 0xFFFF >> bit_count;
                         let mut len = (token & len_mask) as usize + 3;
                         let offset = ((token & !len_mask) >> (16 - bit_count)) as usize + 1;
+                        if offset > decomp_len {
+                            return Err(CfbError::Invalid {
+                                name: "copy token offset",
+                                expected: "an offset within the decompressed chunk",
+                                found: offset as u16,
+                            });
+                        }
 
                         while len > offset {
                             buf[..offset].copy_from_slice(&res[res.len() - offset..]);
@@ -473,3 +638,47 @@ impl XlsEncoding {
         s
     }
 }
+
+#[test]
+fn test_decompress_invalid_stream() {
+    assert_eq!(
+        decompress_stream(&[0x01, 0x03, 0xB0, 0x00, b'a', b'b', b'c']).unwrap(),
+        b"abc"
+    );
+    assert!(decompress_stream(&[]).is_err());
+    assert!(decompress_stream(&[0x02]).is_err());
+    // truncated chunk header
+    assert!(decompress_stream(&[0x01, 0x03]).is_err());
+    // invalid chunk signature
+    assert!(decompress_stream(&[0x01, 0x03, 0x80, 0x00, b'a']).is_err());
+    // truncated uncompressed chunk
+    assert!(decompress_stream(&[0x01, 0xFF, 0x3F, b'a']).is_err());
+    // copy token pointing before the chunk start
+    assert!(decompress_stream(&[0x01, 0x02, 0xB0, 0x01, 0x00, 0x10]).is_err());
+}
+
+#[test]
+fn test_unaligned_mini_stream() {
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut data = Vec::new();
+    zip.by_name("xl/vbaProject.bin")
+        .unwrap()
+        .read_to_end(&mut data)
+        .unwrap();
+    let mut r = &*data;
+    let mut cfb = Cfb::new(&mut r, data.len()).unwrap();
+    let expected = cfb.get_stream("PROJECT", &mut r).unwrap();
+
+    // the root entry (first entry of the first directory sector) announces the size of the
+    // mini stream: 11200 bytes, i.e. 175 mini sectors, the last 9 bytes being unused
+    let size = 1024 + 120;
+    assert_eq!(read_u32(&data[size..size + 4]), 11200);
+    data[size..size + 4].copy_from_slice(&11191u32.to_le_bytes());
+    let mut r = &*data;
+    let mut cfb = Cfb::new(&mut r, data.len()).unwrap();
+    let remaining = r.len();
+    assert_eq!(cfb.get_stream("PROJECT", &mut r).unwrap(), expected);
+    // mini sectors are all in memory, the last one must not be completed from the reader
+    assert_eq!(r.len(), remaining);
+}
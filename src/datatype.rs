@@ -1,12 +1,15 @@
 use std::fmt;
 
 use serde::de::Visitor;
-use serde::{self, Deserialize};
+use serde::{self, Deserialize, Serialize};
 
 use super::CellErrorType;
 
 /// An enum to represent all different data types that can appear as
 /// a value in a worksheet cell
+///
+/// Floating point values are compared exactly by `PartialEq`, use
+/// [`DataType::approx_eq`] to compare values computed differently
 #[derive(Debug, Clone, PartialEq)]
 pub enum DataType {
     /// Unsigned integer
@@ -19,12 +22,53 @@ pub enum DataType {
     Bool(bool),
     /// Date or Time
     DateTime(f64),
+    /// Duration (elapsed time), in fractional days
+    Duration(f64),
     /// Error
     Error(CellErrorType),
     /// Empty cell
+    ///
+    /// Only used for cells without any value: a cell holding an empty string
+    /// is a `String("")`
     Empty,
 }
 
+/// Parses a plain decimal number, with optional thousands separators
+fn parse_number(s: &str) -> Option<f64> {
+    let is_numeric = s.bytes().all(|b| {
+        b.is_ascii_digit()
+            || b == b'.'
+            || b == b','
+            || b == b'-'
+            || b == b'+'
+            || b == b'e'
+            || b == b'E'
+    });
+    if !is_numeric || !s.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !s.contains(',') {
+        return s.parse().ok().filter(|n: &f64| n.is_finite());
+    }
+    // separators are only valid in the integer part, every 3 digits
+    let unsigned = s.trim_start_matches(|c| c == '+' || c == '-');
+    let int_len = unsigned
+        .find(|c| c == '.' || c == 'e' || c == 'E')
+        .unwrap_or(unsigned.len());
+    if unsigned[int_len..].contains(',') {
+        return None;
+    }
+    let mut groups = unsigned[..int_len].split(',');
+    let first = groups.next()?;
+    if first.is_empty() || first.len() > 3 || groups.any(|g| g.len() != 3) {
+        return None;
+    }
+    s.replace(',', "")
+        .parse()
+        .ok()
+        .filter(|n: &f64| n.is_finite())
+}
+
 impl Default for DataType {
     fn default() -> DataType {
         DataType::Empty
@@ -102,6 +146,52 @@ impl DataType {
         }
     }
 
+    /// Compare two data types, accepting floating point values
+    /// (`Float`, `DateTime` and `Duration`) differing by at most `epsilon`
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::DataType;
+    ///
+    /// let a = DataType::Float(0.1 + 0.2);
+    /// assert_ne!(a, DataType::Float(0.3));
+    /// assert!(a.approx_eq(&DataType::Float(0.3), 1e-9));
+    /// assert!(!a.approx_eq(&DataType::DateTime(0.3), 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &DataType, epsilon: f64) -> bool {
+        match (self, other) {
+            (DataType::Float(a), DataType::Float(b))
+            | (DataType::DateTime(a), DataType::DateTime(b))
+            | (DataType::Duration(a), DataType::Duration(b)) => (a - b).abs() <= epsilon,
+            _ => self == other,
+        }
+    }
+
+    /// Try getting a number out of numeric values or of text holding a number
+    ///
+    /// `Int` and `Float` values are returned as is. `String` values are parsed after
+    /// trimming their surrounding whitespaces: they may have a sign, a fraction, an
+    /// exponent and thousands separators (`1,234.5`). Texts such as `inf` or `NaN` are
+    /// not numbers. Any other data type returns `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::DataType;
+    ///
+    /// assert_eq!(DataType::Int(3).coerce_number(), Some(3.));
+    /// assert_eq!(DataType::String(" +1,234.5 ".to_string()).coerce_number(), Some(1234.5));
+    /// assert_eq!(DataType::String("12,34".to_string()).coerce_number(), None);
+    /// assert_eq!(DataType::Bool(true).coerce_number(), None);
+    /// ```
+    pub fn coerce_number(&self) -> Option<f64> {
+        match self {
+            DataType::Int(v) => Some(*v as f64),
+            DataType::Float(v) => Some(*v),
+            DataType::String(s) => parse_number(s.trim()),
+            _ => None,
+        }
+    }
+
     /// Try converting data type into a date
     #[cfg(feature = "dates")]
     pub fn as_date(&self) -> Option<chrono::NaiveDate> {
@@ -133,6 +223,18 @@ impl DataType {
             _ => None,
         }
     }
+
+    /// Try converting data type into a duration
+    #[cfg(feature = "dates")]
+    pub fn as_duration(&self) -> Option<chrono::Duration> {
+        match self {
+            DataType::Int(x) => Some(chrono::Duration::days(*x)),
+            DataType::Float(f) | DataType::Duration(f) => Some(chrono::Duration::milliseconds(
+                (f * 86_400_000.).round() as i64,
+            )),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq<str> for DataType {
@@ -178,7 +280,7 @@ impl fmt::Display for DataType {
             DataType::Float(ref e) => write!(f, "{}", e),
             DataType::String(ref e) => write!(f, "{}", e),
             DataType::Bool(ref e) => write!(f, "{}", e),
-            DataType::DateTime(ref e) => write!(f, "{}", e),
+            DataType::DateTime(ref e) | DataType::Duration(ref e) => write!(f, "{}", e),
             DataType::Error(ref e) => write!(f, "{}", e),
             DataType::Empty => Ok(()),
         }
@@ -256,6 +358,26 @@ impl<'de> Deserialize<'de> for DataType {
     }
 }
 
+/// Serializes cells as their underlying value: `Empty` is serialized as a unit (e.g. `null`),
+/// `DateTime` as its serial number, `Duration` as its number of days and `Error` as its string representation (e.g. `#N/A`)
+impl Serialize for DataType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            DataType::Int(v) => serializer.serialize_i64(*v),
+            DataType::Float(v) | DataType::DateTime(v) | DataType::Duration(v) => {
+                serializer.serialize_f64(*v)
+            }
+            DataType::String(v) => serializer.serialize_str(v),
+            DataType::Bool(v) => serializer.serialize_bool(*v),
+            DataType::Error(e) => serializer.collect_str(e),
+            DataType::Empty => serializer.serialize_unit(),
+        }
+    }
+}
+
 macro_rules! define_from {
     ($variant:path, $ty:ty) => {
         impl From<$ty> for DataType {
@@ -296,6 +418,68 @@ where
     }
 }
 
+/// An enum to represent all different data types that can appear as
+/// a value in a worksheet cell, borrowing shared strings from the workbook
+/// instead of cloning them
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataTypeRef<'a> {
+    /// Unsigned integer
+    Int(i64),
+    /// Float
+    Float(f64),
+    /// String
+    String(String),
+    /// Shared String
+    SharedString(&'a str),
+    /// Boolean
+    Bool(bool),
+    /// Date or Time
+    DateTime(f64),
+    /// Duration (elapsed time), in fractional days
+    Duration(f64),
+    /// Error
+    Error(CellErrorType),
+    /// Empty cell
+    Empty,
+}
+
+impl<'a> Default for DataTypeRef<'a> {
+    fn default() -> DataTypeRef<'a> {
+        DataTypeRef::Empty
+    }
+}
+
+impl<'a> DataTypeRef<'a> {
+    /// Assess if datatype is empty
+    pub fn is_empty(&self) -> bool {
+        *self == DataTypeRef::Empty
+    }
+    /// Try getting string value
+    pub fn get_string(&self) -> Option<&str> {
+        match self {
+            DataTypeRef::String(v) => Some(&**v),
+            DataTypeRef::SharedString(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<DataTypeRef<'a>> for DataType {
+    fn from(value: DataTypeRef<'a>) -> Self {
+        match value {
+            DataTypeRef::Int(v) => DataType::Int(v),
+            DataTypeRef::Float(v) => DataType::Float(v),
+            DataTypeRef::String(v) => DataType::String(v),
+            DataTypeRef::SharedString(v) => DataType::String(v.into()),
+            DataTypeRef::Bool(v) => DataType::Bool(v),
+            DataTypeRef::DateTime(v) => DataType::DateTime(v),
+            DataTypeRef::Duration(v) => DataType::Duration(v),
+            DataTypeRef::Error(v) => DataType::Error(v),
+            DataTypeRef::Empty => DataType::Empty,
+        }
+    }
+}
+
 #[cfg(all(test, feature = "dates"))]
 mod tests {
     use super::*;
@@ -571,7 +571,7 @@ impl<'a, 'de> serde::Deserializer<'de> for DataTypeDeserializer<'a> {
             DataType::Bool(v) => visitor.visit_bool(*v),
             DataType::Int(v) => visitor.visit_i64(*v),
             DataType::Empty => visitor.visit_unit(),
-            DataType::DateTime(v) => visitor.visit_f64(*v),
+            DataType::DateTime(v) | DataType::Duration(v) => visitor.visit_f64(*v),
             DataType::Error(ref err) => Err(DeError::CellError {
                 err: err.clone(),
                 pos: self.pos,
@@ -589,7 +589,7 @@ impl<'a, 'de> serde::Deserializer<'de> for DataTypeDeserializer<'a> {
             DataType::Float(v) => visitor.visit_str(&v.to_string()),
             DataType::Int(v) => visitor.visit_str(&v.to_string()),
             DataType::Bool(v) => visitor.visit_str(&v.to_string()),
-            DataType::DateTime(v) => visitor.visit_str(&v.to_string()),
+            DataType::DateTime(v) | DataType::Duration(v) => visitor.visit_str(&v.to_string()),
             DataType::Error(ref err) => Err(DeError::CellError {
                 err: err.clone(),
                 pos: self.pos,
@@ -640,7 +640,7 @@ impl<'a, 'de> serde::Deserializer<'de> for DataTypeDeserializer<'a> {
             DataType::Empty => visitor.visit_bool(false),
             DataType::Float(v) => visitor.visit_bool(*v != 0.),
             DataType::Int(v) => visitor.visit_bool(*v != 0),
-            DataType::DateTime(v) => visitor.visit_bool(*v != 0.),
+            DataType::DateTime(v) | DataType::Duration(v) => visitor.visit_bool(*v != 0.),
             DataType::Error(ref err) => Err(DeError::CellError {
                 err: err.clone(),
                 pos: self.pos,
@@ -19,6 +19,14 @@ pub enum Error {
     /// cfb specific error
     De(crate::de::DeError),
 
+    /// No sheet matches the requested name
+    SheetNotFound {
+        /// Requested sheet name
+        requested: String,
+        /// Names of the sheets of the workbook, in workbook order
+        available: Vec<String>,
+    },
+
     /// General error message
     Msg(&'static str),
 }
@@ -42,6 +50,15 @@ impl std::fmt::Display for Error {
             Error::Xlsb(e) => write!(f, "Xlsb error: {}", e),
             Error::Vba(e) => write!(f, "Vba error: {}", e),
             Error::De(e) => write!(f, "Deserializer error: {}", e),
+            Error::SheetNotFound {
+                requested,
+                available,
+            } => write!(
+                f,
+                "Sheet '{}' does not exist, available sheets are: '{}'",
+                requested,
+                available.join("', '")
+            ),
             Error::Msg(msg) => write!(f, "{}", msg),
         }
     }
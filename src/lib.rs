@@ -76,20 +76,24 @@ pub mod vba;
 use serde::de::DeserializeOwned;
 use std::borrow::Cow;
 use std::cmp::{max, min};
+use std::collections::HashMap;
 use std::fmt;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek};
+use std::io::{BufReader, Read, Seek, Write};
 use std::ops::{Index, IndexMut};
 use std::path::Path;
 
 pub use crate::auto::{open_workbook_auto, Sheets};
-pub use crate::datatype::DataType;
+pub use crate::datatype::{DataType, DataTypeRef};
 pub use crate::de::{DeError, RangeDeserializer, RangeDeserializerBuilder, ToCellDeserializer};
 pub use crate::errors::Error;
 pub use crate::ods::{Ods, OdsError};
 pub use crate::xls::{Xls, XlsError};
 pub use crate::xlsb::{Xlsb, XlsbError};
-pub use crate::xlsx::{Xlsx, XlsxError};
+pub use crate::xlsx::{
+    CalcMode, CalcProperties, ConditionalFormat, ConditionalFormatRule, DataValidation,
+    DocProperties, Pane, PaneState, PivotCacheInfo, SheetFormat, Xlsx, XlsxError, XlsxOptions,
+};
 
 use crate::vba::VbaProject;
 
@@ -131,6 +135,73 @@ impl fmt::Display for CellErrorType {
     }
 }
 
+/// Date system of a workbook, i.e. the date corresponding to the serial number 0
+///
+/// Dates are stored as a number of days since this origin: the same serial number
+/// represents dates 1462 days apart depending on the date system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSystem {
+    /// 1900 date system (default), serial 1 is 1900-01-01
+    V1900,
+    /// 1904 date system (older Mac workbooks), serial 0 is 1904-01-01
+    V1904,
+}
+
+impl Default for DateSystem {
+    fn default() -> DateSystem {
+        DateSystem::V1900
+    }
+}
+
+/// Visibility of a sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetVisible {
+    /// Visible
+    Visible,
+    /// Hidden, can be unhidden by the user
+    Hidden,
+    /// Hidden, can only be unhidden programmatically (e.g. from a VBA macro)
+    VeryHidden,
+}
+
+impl Default for SheetVisible {
+    fn default() -> SheetVisible {
+        SheetVisible::Visible
+    }
+}
+
+/// Type of a sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetType {
+    /// Worksheet, a grid of cells
+    Worksheet,
+    /// Chart sheet, without any cell
+    ChartSheet,
+    /// Dialog sheet (Excel 5 dialog), without any cell
+    DialogSheet,
+    /// Excel 4 macro sheet
+    MacroSheet,
+}
+
+impl Default for SheetType {
+    fn default() -> SheetType {
+        SheetType::Worksheet
+    }
+}
+
+/// Metadata of a sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet {
+    /// Name
+    pub name: String,
+    /// Visibility
+    pub visible: SheetVisible,
+    /// Type of sheet
+    ///
+    /// Only read from xlsx workbooks, other formats always report worksheets.
+    pub typ: SheetType,
+}
+
 /// Common file metadata
 ///
 /// Depending on file type, some extra information may be stored
@@ -140,6 +211,20 @@ pub struct Metadata {
     sheets: Vec<String>,
     /// Map of sheet names/sheet path within zip archive
     names: Vec<(String, String)>,
+    /// Sheets metadata, in workbook order
+    sheets_metadata: Vec<Sheet>,
+}
+
+impl Metadata {
+    /// Register a new sheet
+    pub(crate) fn push_sheet(&mut self, name: String, visible: SheetVisible) {
+        self.sheets.push(name.clone());
+        self.sheets_metadata.push(Sheet {
+            name,
+            visible,
+            typ: SheetType::default(),
+        });
+    }
 }
 
 // FIXME `Reader` must only be seek `Seek` for `Xls::xls`. Because of the present API this limits
@@ -154,10 +239,15 @@ pub trait Reader: Sized {
     /// Creates a new instance.
     fn new(reader: Self::RS) -> Result<Self, Self::Error>;
     /// Gets `VbaProject`
+    ///
+    /// The project is parsed once and then borrowed by the following calls.
     fn vba_project(&mut self) -> Option<Result<Cow<'_, VbaProject>, Self::Error>>;
     /// Initialize
     fn metadata(&self) -> &Metadata;
     /// Read worksheet data in corresponding worksheet path
+    ///
+    /// Returns `None` if there is no such worksheet, `Some(Err(_))` if it cannot be read.
+    /// A worksheet without any cell is read as an empty `Range` (see `Range::is_empty`).
     fn worksheet_range(&mut self, name: &str) -> Option<Result<Range<DataType>, Self::Error>>;
 
     /// Fetch all worksheet data & paths
@@ -180,17 +270,101 @@ pub trait Reader: Sized {
         &self.metadata().sheets
     }
 
+    /// Get the metadata (name and visibility) of all sheets, in workbook order
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook, Reader, SheetVisible};
+    ///
+    /// # let path = format!("{}/tests/sheet_visibility.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let visible = workbook
+    ///     .sheets_metadata()
+    ///     .iter()
+    ///     .filter(|s| s.visible == SheetVisible::Visible)
+    ///     .map(|s| &*s.name)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(visible, ["Visible"]);
+    /// ```
+    fn sheets_metadata(&self) -> &[Sheet] {
+        &self.metadata().sheets_metadata
+    }
+
     /// Get all defined names (Ranges names etc)
     fn defined_names(&self) -> &[(String, String)] {
         &self.metadata().names
     }
 
+    /// Read worksheet data in corresponding worksheet path, failing with
+    /// `Error::SheetNotFound` if there is no such worksheet
+    ///
+    /// Unlike `worksheet_range`, a missing worksheet is an error, which lists the sheet names
+    /// of the workbook.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Error, Xlsx, open_workbook, Reader};
+    ///
+    /// # let path = format!("{}/tests/temperature.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// assert!(workbook.try_worksheet_range("Sheet1").is_ok());
+    /// match workbook.try_worksheet_range("sheet1") {
+    ///     Err(Error::SheetNotFound { available, .. }) => assert_eq!(available, ["Sheet1"]),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    fn try_worksheet_range(&mut self, name: &str) -> Result<Range<DataType>, Error>
+    where
+        Error: From<Self::Error>,
+    {
+        match self.worksheet_range(name) {
+            Some(range) => Ok(range?),
+            None => Err(Error::SheetNotFound {
+                requested: name.to_string(),
+                available: self.sheet_names().to_vec(),
+            }),
+        }
+    }
+
     /// Get the nth worksheet. Shortcut for getting the nth
     /// sheet_name, then the corresponding worksheet.
     fn worksheet_range_at(&mut self, n: usize) -> Option<Result<Range<DataType>, Self::Error>> {
         let name = self.sheet_names().get(n)?.to_string();
         self.worksheet_range(&name)
     }
+
+    /// Read all visible worksheets, by name
+    ///
+    /// Unlike `worksheets`, hidden sheets are skipped and the first worksheet which cannot
+    /// be read fails the whole call. Workbook wide data (e.g. shared strings) is only read
+    /// once, when opening the workbook.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook, Reader};
+    ///
+    /// # let path = format!("{}/tests/sheet_visibility.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let ranges = workbook.all_ranges().unwrap();
+    /// assert_eq!(ranges.len(), 1);
+    /// assert!(ranges.contains_key("Visible"));
+    /// ```
+    fn all_ranges(&mut self) -> Result<HashMap<String, Range<DataType>>, Self::Error> {
+        let names = self
+            .sheets_metadata()
+            .iter()
+            .filter(|s| s.visible == SheetVisible::Visible)
+            .map(|s| s.name.clone())
+            .collect::<Vec<_>>();
+        let mut ranges = HashMap::with_capacity(names.len());
+        for name in names {
+            // skip sheets which are not worksheets (e.g. chart sheets)
+            if let Some(range) = self.worksheet_range(&name) {
+                ranges.insert(name, range?);
+            }
+        }
+        Ok(ranges)
+    }
 }
 
 /// Convenient function to open a file with a BufReader<File>
@@ -236,8 +410,41 @@ impl<T: CellType> Cell<T> {
     }
 }
 
+/// A struct which represents the bounds of a squared selection of cells
+///
+/// Both positions are absolute (row, column) positions and are inclusive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dimensions {
+    /// Top left cell position (row, column)
+    pub start: (u32, u32),
+    /// Bottom right cell position (row, column)
+    pub end: (u32, u32),
+}
+
+impl Dimensions {
+    /// Creates a new `Dimensions`
+    pub fn new(start: (u32, u32), end: (u32, u32)) -> Dimensions {
+        Dimensions { start, end }
+    }
+
+    /// Checks if the (row, column) absolute position is within these dimensions
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        row >= self.start.0 && row <= self.end.0 && col >= self.start.1 && col <= self.end.1
+    }
+
+    /// Number of cells within these dimensions
+    ///
+    /// Inverted dimensions (e.g. read from an invalid file) count as a single row or column.
+    pub(crate) fn len(&self) -> u64 {
+        (self.end.0.saturating_sub(self.start.0) as u64 + 1)
+            * (self.end.1.saturating_sub(self.start.1) as u64 + 1)
+    }
+}
+
 /// A struct which represents a squared selection of cells
-#[derive(Debug, Default, Clone)]
+///
+/// Two ranges are equal when they have the same start, end and cells
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Range<T: CellType> {
     start: (u32, u32),
     end: (u32, u32),
@@ -263,6 +470,20 @@ impl<T: CellType> Range<T> {
     }
 
     /// Creates a new empty range
+    ///
+    /// An empty range has no cell: `start` and `end` are `None`, `get_value` and `get` always
+    /// return `None` and `rows` doesn't yield anything.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let range: Range<DataType> = Range::empty();
+    /// assert!(range.is_empty());
+    /// assert_eq!(range.start(), None);
+    /// assert_eq!(range.get_value((0, 0)), None);
+    /// assert_eq!(range.rows().count(), 0);
+    /// ```
     #[inline]
     pub fn empty() -> Range<T> {
         Range {
@@ -292,7 +513,7 @@ impl<T: CellType> Range<T> {
         }
     }
 
-    /// Get column width
+    /// Get column width (the number of columns)
     #[inline]
     pub fn width(&self) -> usize {
         if self.is_empty() {
@@ -302,7 +523,7 @@ impl<T: CellType> Range<T> {
         }
     }
 
-    /// Get column height
+    /// Get row height (the number of rows)
     #[inline]
     pub fn height(&self) -> usize {
         if self.is_empty() {
@@ -313,12 +534,27 @@ impl<T: CellType> Range<T> {
     }
 
     /// Get size in (height, width) format
+    ///
+    /// Prefer `height` and `width` methods when only one of them is needed.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// // 6 rows and 3 columns
+    /// let range: Range<DataType> = Range::new((0, 0), (5, 2));
+    /// assert_eq!(range.get_size(), (6, 3));
+    /// assert_eq!(range.get_size(), (range.height(), range.width()));
+    /// ```
     #[inline]
     pub fn get_size(&self) -> (usize, usize) {
         (self.height(), self.width())
     }
 
     /// Is range empty
+    ///
+    /// An empty range has no cell at all: its `start` and `end` are `None` and its
+    /// size is `(0, 0)`.
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
@@ -355,7 +591,6 @@ impl<T: CellType> Range<T> {
             let width = col_end - col_start + 1;
             let len = ((row_end - row_start + 1) * width) as usize;
             let mut v = vec![T::default(); len];
-            v.shrink_to_fit();
             for c in cells {
                 let idx = ((c.pos.0 - row_start) * width + (c.pos.1 - col_start)) as usize;
                 v[idx] = c.val;
@@ -368,6 +603,50 @@ impl<T: CellType> Range<T> {
         }
     }
 
+    /// Creates a `Range` starting at `position` (row, column) out of its cells, row by row
+    ///
+    /// The height of the range is `data.len() / width`. An empty `data` gives an empty range.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `data.len()` is not a multiple of `width` or if the range would go beyond
+    /// `u32::MAX` rows or columns.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let data = vec![DataType::Int(1), DataType::Int(2), DataType::Int(3), DataType::Int(4)];
+    /// let range = Range::from_vec((1, 2), 2, data).unwrap();
+    /// assert_eq!(range.get_size(), (2, 2));
+    /// assert_eq!(range.get_value((2, 2)), Some(&DataType::Int(3)));
+    /// assert!(Range::from_vec((0, 0), 3, vec![DataType::Int(1)]).is_err());
+    /// ```
+    pub fn from_vec(position: (u32, u32), width: usize, data: Vec<T>) -> Result<Range<T>, Error> {
+        if data.is_empty() {
+            return Ok(Range::empty());
+        }
+        if width == 0 || data.len() % width != 0 {
+            return Err(Error::Msg("data length must be a multiple of width"));
+        }
+        let height = data.len() / width;
+        let last = |start: u32, len: usize| {
+            if len > std::u32::MAX as usize {
+                None
+            } else {
+                start.checked_add(len as u32 - 1)
+            }
+        };
+        match (last(position.0, height), last(position.1, width)) {
+            (Some(row), Some(col)) => Ok(Range {
+                start: position,
+                end: (row, col),
+                inner: data,
+            }),
+            _ => Err(Error::Msg("range out of bounds")),
+        }
+    }
+
     /// Set inner value from absolute position
     ///
     /// # Remarks
@@ -477,10 +756,97 @@ impl<T: CellType> Range<T> {
         None
     }
 
+    /// Get a mutable reference to the cell value from **absolute position**.
+    ///
+    /// If the `absolute_position` is out of range, returns `None`. Unlike `set_value`, the range
+    /// is never resized. The coordinate format is (row, column).
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((1, 1), (2, 2));
+    /// range.set_value((2, 1), DataType::Int(2));
+    /// if let Some(DataType::Int(v)) = range.get_value_mut((2, 1)) {
+    ///     *v *= 10;
+    /// }
+    /// assert_eq!(range.get_value((2, 1)), Some(&DataType::Int(20)));
+    /// assert_eq!(range.get_value_mut((0, 0)), None);
+    /// ```
+    pub fn get_value_mut(&mut self, absolute_position: (u32, u32)) -> Option<&mut T> {
+        let p = absolute_position;
+        if p.0 >= self.start.0 && p.0 <= self.end.0 && p.1 >= self.start.1 && p.1 <= self.end.1 {
+            let width = self.width();
+            let idx = (p.0 - self.start.0) as usize * width + (p.1 - self.start.1) as usize;
+            return self.inner.get_mut(idx);
+        }
+        None
+    }
+
     /// Get cell value from **relative position**.
+    ///
+    /// Unlike using the Index trait, this will not panic but rather yield `None` if out of range.
+    /// Otherwise, returns the cell value. The coordinate format is (row, column).
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((1, 1), (2, 2));
+    /// range.set_value((2, 1), DataType::Int(2));
+    /// assert_eq!(range.get((1, 0)), Some(&DataType::Int(2)));
+    /// assert_eq!(range[(1, 0)], DataType::Int(2));
+    /// // column 2 does not exist, it must not wrap to the next row
+    /// assert_eq!(range.get((0, 2)), None);
+    /// ```
     pub fn get(&self, relative_position: (usize, usize)) -> Option<&T> {
         let (row, col) = relative_position;
-        self.inner.get(row * self.width() + col)
+        let (height, width) = self.get_size();
+        if col >= width || row >= height {
+            None
+        } else {
+            self.inner.get(row * width + col)
+        }
+    }
+
+    /// Get a row by its index, relative to the start of the range
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((1, 1), (3, 2));
+    /// range.set_value((2, 2), DataType::Int(2));
+    /// assert_eq!(range.row(1), Some(&[DataType::Empty, DataType::Int(2)][..]));
+    /// assert_eq!(range.row(3), None);
+    /// ```
+    pub fn row(&self, i: usize) -> Option<&[T]> {
+        let (height, width) = self.get_size();
+        if i >= height {
+            None
+        } else {
+            Some(&self.inner[i * width..(i + 1) * width])
+        }
+    }
+
+    /// Get a column by its index, relative to the start of the range
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((1, 1), (3, 2));
+    /// range.set_value((2, 2), DataType::Int(2));
+    /// assert_eq!(range.col(1), Some(vec![&DataType::Empty, &DataType::Int(2), &DataType::Empty]));
+    /// assert_eq!(range.col(2), None);
+    /// ```
+    pub fn col(&self, j: usize) -> Option<Vec<&T>> {
+        let width = self.width();
+        if j >= width {
+            None
+        } else {
+            Some(self.inner[j..].iter().step_by(width).collect())
+        }
     }
 
     /// Get an iterator over inner rows
@@ -504,6 +870,55 @@ impl<T: CellType> Range<T> {
         }
     }
 
+    /// Get a mutable iterator over inner rows
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range: Range<DataType> = Range::new((0, 0), (1, 1));
+    /// for row in range.rows_mut() {
+    ///     row[1] = DataType::Bool(true);
+    /// }
+    /// assert_eq!(range.get_value((1, 1)), Some(&DataType::Bool(true)));
+    /// assert_eq!(range.get_value((1, 0)), Some(&DataType::Empty));
+    /// ```
+    pub fn rows_mut(&mut self) -> RowsMut<'_, T> {
+        if self.inner.is_empty() {
+            RowsMut { inner: None }
+        } else {
+            let width = self.width();
+            RowsMut {
+                inner: Some(self.inner.chunks_mut(width)),
+            }
+        }
+    }
+
+    /// Get an iterator over inner columns
+    ///
+    /// As cells are stored row by row, each column is yielded as a strided
+    /// iterator over the column cells, from top to bottom.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range: Range<DataType> = Range::new((0, 0), (5, 2));
+    /// range.set_value((3, 1), DataType::Int(1));
+    /// assert_eq!(range.columns().count(), 3);
+    /// // with columns item column: impl Iterator<Item = &DataType>
+    /// assert_eq!(range.columns().map(|c| c.count()).sum::<usize>(), 18);
+    /// assert_eq!(range.columns().nth(1).unwrap().nth(3), Some(&DataType::Int(1)));
+    /// ```
+    pub fn columns(&self) -> Columns<'_, T> {
+        let width = self.width();
+        Columns {
+            width,
+            inner: &self.inner,
+            cols: 0..width,
+        }
+    }
+
     /// Get an iterator over used cells only
     pub fn used_cells(&self) -> UsedCells<'_, T> {
         UsedCells {
@@ -520,6 +935,200 @@ impl<T: CellType> Range<T> {
         }
     }
 
+    /// Get an iterator over all cells in this range, with their absolute positions
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((2, 3), (3, 3));
+    /// range.set_value((3, 3), DataType::Int(1));
+    /// let cells = range.cell_iter().collect::<Vec<_>>();
+    /// assert_eq!(cells, [(2, 3, &DataType::Empty), (3, 3, &DataType::Int(1))]);
+    /// ```
+    pub fn cell_iter(&self) -> CellIter<'_, T> {
+        CellIter {
+            start: self.start,
+            inner: self.cells(),
+        }
+    }
+
+    /// Get the relative position `(row, col)` of the first cell matching `pred`
+    ///
+    /// Cells are searched row by row.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((1, 0), (3, 1));
+    /// range.set_value((3, 0), DataType::String("Total".to_string()));
+    /// range.set_value((3, 1), DataType::Float(42.));
+    ///
+    /// let (row, _) = range.find(|c| c == &DataType::String("Total".to_string())).unwrap();
+    /// assert_eq!(range.get((row, 1)), Some(&DataType::Float(42.)));
+    /// assert_eq!(range.find(|c| c == &DataType::Bool(true)), None);
+    /// ```
+    pub fn find<F: Fn(&T) -> bool>(&self, pred: F) -> Option<(usize, usize)> {
+        self.find_all(pred).next()
+    }
+
+    /// Get an iterator over the relative positions `(row, col)` of all cells matching `pred`
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((0, 0), (1, 1));
+    /// range.set_value((0, 1), DataType::Int(1));
+    /// range.set_value((1, 0), DataType::Int(2));
+    ///
+    /// let found = range.find_all(|c| c != &DataType::Empty).collect::<Vec<_>>();
+    /// assert_eq!(found, [(0, 1), (1, 0)]);
+    /// ```
+    pub fn find_all<F: Fn(&T) -> bool>(&self, pred: F) -> FindAll<'_, T, F> {
+        FindAll {
+            inner: self.cells(),
+            pred,
+        }
+    }
+
+    /// Copy all cells into a `Vec` of rows
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range: Range<DataType> = Range::new((0, 0), (1, 1));
+    /// range.set_value((1, 0), DataType::Int(1));
+    /// assert_eq!(
+    ///     range.to_vec(),
+    ///     vec![
+    ///         vec![DataType::Empty, DataType::Empty],
+    ///         vec![DataType::Int(1), DataType::Empty],
+    ///     ]
+    /// );
+    /// assert!(Range::<DataType>::empty().to_vec().is_empty());
+    /// ```
+    pub fn to_vec(&self) -> Vec<Vec<T>> {
+        self.rows().map(|r| r.to_vec()).collect()
+    }
+
+    /// Get a copy of the range without its leading and trailing empty rows and columns
+    ///
+    /// The result is the smallest range containing all the used (non default) cells,
+    /// cells keep their absolute positions. A range without any used cell is trimmed to
+    /// an empty range.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((0, 0), (5, 5));
+    /// range.set_value((1, 2), DataType::Int(1));
+    /// range.set_value((3, 1), DataType::Int(2));
+    ///
+    /// let trimmed = range.trim();
+    /// assert_eq!(trimmed.start(), Some((1, 1)));
+    /// assert_eq!(trimmed.end(), Some((3, 2)));
+    /// assert_eq!(trimmed.get_value((3, 1)), Some(&DataType::Int(2)));
+    ///
+    /// assert!(Range::<DataType>::new((0, 0), (1, 1)).trim().is_empty());
+    /// ```
+    pub fn trim(&self) -> Range<T> {
+        let mut used = self.used_cells();
+        let (row, col, _) = match used.next() {
+            Some(c) => c,
+            None => return Range::empty(),
+        };
+        let (mut start, mut end) = ((row, col), (row, col));
+        for (row, col, _) in used {
+            start.1 = min(start.1, col);
+            end.0 = max(end.0, row);
+            end.1 = max(end.1, col);
+        }
+        self.range(
+            (self.start.0 + start.0 as u32, self.start.1 + start.1 as u32),
+            (self.start.0 + end.0 as u32, self.start.1 + end.1 as u32),
+        )
+    }
+
+    /// Get a copy of the range without consecutive duplicate rows
+    ///
+    /// Only the first row of each run of identical rows is kept. The range keeps its start,
+    /// following rows are moved up to fill the gaps.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((1, 0), (4, 0));
+    /// range.set_value((1, 0), DataType::Int(1));
+    /// range.set_value((2, 0), DataType::Int(1));
+    /// range.set_value((3, 0), DataType::Int(2));
+    /// range.set_value((4, 0), DataType::Int(1));
+    ///
+    /// let deduplicated = range.deduplicate_rows();
+    /// assert_eq!(deduplicated.start(), Some((1, 0)));
+    /// assert_eq!(deduplicated.end(), Some((3, 0)));
+    /// assert_eq!(deduplicated.get_value((3, 0)), Some(&DataType::Int(1)));
+    /// ```
+    pub fn deduplicate_rows(&self) -> Range<T> {
+        let mut inner: Vec<T> = Vec::with_capacity(self.inner.len());
+        let mut previous: Option<&[T]> = None;
+        let mut height = 0;
+        for row in self.rows() {
+            if previous != Some(row) {
+                inner.extend_from_slice(row);
+                height += 1;
+            }
+            previous = Some(row);
+        }
+        if height == 0 {
+            return Range::empty();
+        }
+        Range {
+            start: self.start,
+            end: (self.start.0 + height - 1, self.end.1),
+            inner,
+        }
+    }
+
+    /// Get a transposed copy of the range, where rows become columns
+    ///
+    /// The cell at absolute position `(row, col)` is moved to `(col, row)`, i.e. the
+    /// range is mirrored along the sheet diagonal: a range spanning *B1:D2* becomes *A2:B4*.
+    /// As a consequence, transposing twice gives back the original range.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((0, 1), (1, 3));
+    /// range.set_value((0, 3), DataType::Int(1));
+    ///
+    /// let transposed = range.transpose();
+    /// assert_eq!(transposed.start(), Some((1, 0)));
+    /// assert_eq!(transposed.end(), Some((3, 1)));
+    /// assert_eq!(transposed.get_value((3, 0)), Some(&DataType::Int(1)));
+    /// ```
+    pub fn transpose(&self) -> Range<T> {
+        if self.inner.is_empty() {
+            return Range::empty();
+        }
+        let (height, width) = self.get_size();
+        let mut inner = Vec::with_capacity(self.inner.len());
+        for col in 0..width {
+            inner.extend(self.inner[col..].iter().step_by(width).cloned());
+        }
+        debug_assert_eq!(inner.len(), height * width);
+        Range {
+            start: (self.start.1, self.start.0),
+            end: (self.end.1, self.end.0),
+            inner,
+        }
+    }
+
     /// Build a `RangeDeserializer` from this configuration.
     ///
     /// # Example
@@ -556,7 +1165,13 @@ impl<T: CellType> Range<T> {
     ///
     /// # Remarks
     ///
-    /// Cells within this range will be cloned, cells out of it will be set to Empty
+    /// Cells within this range will be cloned, cells out of it will be set to Empty.
+    /// The returned `Range` always spans exactly from `start` to `end` (absolute positions),
+    /// it is never clamped to the bounds of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if start.0 > end.0 or start.1 > end.1
     ///
     /// # Example
     ///
@@ -574,9 +1189,17 @@ impl<T: CellType> Range<T> {
     /// assert_eq!(c.get_value((0, 0)), Some(&DataType::Empty));
     /// assert_eq!(c.get_value((1, 1)), Some(&DataType::Bool(true)));
     /// assert_eq!(c.get_value((2, 2)), Some(&DataType::Bool(true)));
+    ///
+    /// // windows outside of the source range are filled with Empty cells
+    /// let d = Range::<DataType>::empty().range((0, 0), (1, 1));
+    /// assert_eq!(d.get_size(), (2, 2));
+    /// assert!(d.cells().all(|(_, _, v)| v.is_empty()));
     /// ```
     pub fn range(&self, start: (u32, u32), end: (u32, u32)) -> Range<T> {
         let mut other = Range::new(start, end);
+        if self.is_empty() {
+            return other;
+        }
         let (self_start_row, self_start_col) = self.start;
         let (self_end_row, self_end_col) = self.end;
         let (other_start_row, other_start_col) = other.start;
@@ -633,6 +1256,158 @@ impl<T: CellType> Range<T> {
     }
 }
 
+/// Iterates over the rows of a `Range`, same as `Range::rows`
+///
+/// # Examples
+/// ```
+/// use calamine::{DataType, Range};
+///
+/// let range: Range<DataType> = Range::new((0, 0), (5, 2));
+/// let mut count = 0;
+/// for row in &range {
+///     count += row.len();
+/// }
+/// assert_eq!(count, 18);
+/// ```
+impl<'a, T: CellType> IntoIterator for &'a Range<T> {
+    type Item = &'a [T];
+    type IntoIter = Rows<'a, T>;
+    fn into_iter(self) -> Rows<'a, T> {
+        self.rows()
+    }
+}
+
+/// Serializes a `Range` as a sequence of rows, each row being a sequence of cells
+///
+/// Positions are not serialized.
+impl<T: CellType + serde::Serialize> serde::Serialize for Range<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+impl<T: CellType + fmt::Display> Range<T> {
+    /// Writes the range as csv, following RFC 4180
+    ///
+    /// Cells are written using their `Display` implementation (empty cells are blank),
+    /// separated by commas. Rows end with CRLF. Cells containing a comma, a double quote
+    /// or a line break are enclosed in double quotes, inner double quotes being doubled.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Range, DataType};
+    ///
+    /// let mut range = Range::new((0, 0), (1, 2));
+    /// range.set_value((0, 0), DataType::String("a, \"b\"".to_string()));
+    /// range.set_value((0, 2), DataType::Float(1.5));
+    /// range.set_value((1, 1), DataType::Int(2));
+    ///
+    /// let mut csv = Vec::new();
+    /// range.write_csv(&mut csv).unwrap();
+    /// assert_eq!(csv, b"\"a, \"\"b\"\"\",,1.5\r\n,2,\r\n");
+    /// ```
+    pub fn write_csv<W: Write>(&self, mut w: W) -> std::io::Result<()> {
+        for row in self.rows() {
+            for (i, c) in row.iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                let cell = c.to_string();
+                if cell.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+                    write!(w, "\"{}\"", cell.replace('"', "\"\""))?;
+                } else {
+                    w.write_all(cell.as_bytes())?;
+                }
+            }
+            w.write_all(b"\r\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds a `Range` out of `Cell`s given in any order
+///
+/// Unlike `Range::from_sparse`, cells don't need to be sorted by row. The `Range` is the
+/// smallest area containing all the cells, missing cells are filled with default values.
+/// If several cells share the same position, the last one wins.
+///
+/// # Examples
+/// ```
+/// use calamine::{Cell, DataType, Range};
+///
+/// let range: Range<DataType> = vec![
+///     Cell::new((2, 1), DataType::Float(2.)),
+///     Cell::new((1, 3), DataType::String("a".to_string())),
+/// ]
+/// .into_iter()
+/// .collect();
+/// assert_eq!(range.start(), Some((1, 1)));
+/// assert_eq!(range.end(), Some((2, 3)));
+/// assert_eq!(range.get_value((2, 1)), Some(&DataType::Float(2.)));
+/// assert_eq!(range.get_value((1, 1)), Some(&DataType::Empty));
+/// ```
+impl<T: CellType> std::iter::FromIterator<Cell<T>> for Range<T> {
+    fn from_iter<I: IntoIterator<Item = Cell<T>>>(iter: I) -> Range<T> {
+        let cells = iter.into_iter().collect::<Vec<_>>();
+        if cells.is_empty() {
+            return Range::empty();
+        }
+        let mut start = (std::u32::MAX, std::u32::MAX);
+        let mut end = (0, 0);
+        for &Cell {
+            pos: (row, col), ..
+        } in &cells
+        {
+            start = (min(start.0, row), min(start.1, col));
+            end = (max(end.0, row), max(end.1, col));
+        }
+        let mut range = Range::new(start, end);
+        let width = range.width();
+        for c in cells {
+            let idx = (c.pos.0 - start.0) as usize * width + (c.pos.1 - start.1) as usize;
+            range.inner[idx] = c.val;
+        }
+        range
+    }
+}
+
+/// Renders every cell as text, row by row
+///
+/// Numbers use their decimal representation, booleans are written `TRUE` or `FALSE`
+/// (as displayed by Excel), errors use their Excel code and empty cells are empty strings.
+///
+/// # Examples
+/// ```
+/// use calamine::{Range, DataType};
+///
+/// let mut range = Range::new((0, 0), (1, 1));
+/// range.set_value((0, 0), DataType::Float(1.5));
+/// range.set_value((0, 1), DataType::String("a".to_string()));
+/// range.set_value((1, 1), DataType::Bool(true));
+///
+/// let text: Vec<Vec<String>> = range.into();
+/// assert_eq!(text, [["1.5", "a"], ["", "TRUE"]]);
+/// ```
+impl From<Range<DataType>> for Vec<Vec<String>> {
+    fn from(range: Range<DataType>) -> Vec<Vec<String>> {
+        range
+            .rows()
+            .map(|r| {
+                r.iter()
+                    .map(|c| match *c {
+                        DataType::Bool(true) => "TRUE".to_string(),
+                        DataType::Bool(false) => "FALSE".to_string(),
+                        ref c => c.to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
 impl<T: CellType> Index<usize> for Range<T> {
     type Output = [T];
     fn index(&self, index: usize) -> &[T] {
@@ -698,6 +1473,68 @@ impl<'a, T: 'a + CellType> DoubleEndedIterator for Cells<'a, T> {
 
 impl<'a, T: 'a + CellType> ExactSizeIterator for Cells<'a, T> {}
 
+/// A struct to iterate over all cells, with their absolute positions
+#[derive(Debug)]
+pub struct CellIter<'a, T: CellType> {
+    start: (u32, u32),
+    inner: Cells<'a, T>,
+}
+
+impl<'a, T: 'a + CellType> Iterator for CellIter<'a, T> {
+    type Item = (u32, u32, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.start;
+        self.inner
+            .next()
+            .map(|(r, c, v)| (start.0 + r as u32, start.1 + c as u32, v))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T: 'a + CellType> DoubleEndedIterator for CellIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let start = self.start;
+        self.inner
+            .next_back()
+            .map(|(r, c, v)| (start.0 + r as u32, start.1 + c as u32, v))
+    }
+}
+
+impl<'a, T: 'a + CellType> ExactSizeIterator for CellIter<'a, T> {}
+
+/// A struct to iterate over the positions of the cells matching a predicate
+pub struct FindAll<'a, T: CellType, F> {
+    inner: Cells<'a, T>,
+    pred: F,
+}
+
+impl<'a, T: 'a + CellType, F: Fn(&T) -> bool> Iterator for FindAll<'a, T, F> {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let pred = &self.pred;
+        self.inner
+            .by_ref()
+            .find(|&(_, _, v)| pred(v))
+            .map(|(r, c, _)| (r, c))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, up) = self.inner.size_hint();
+        (0, up)
+    }
+}
+
+impl<'a, T: 'a + CellType, F: Fn(&T) -> bool> DoubleEndedIterator for FindAll<'a, T, F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let pred = &self.pred;
+        self.inner
+            .by_ref()
+            .rfind(|&(_, _, v)| pred(v))
+            .map(|(r, c, _)| (r, c))
+    }
+}
+
 /// A struct to iterate over used cells
 #[derive(Debug)]
 pub struct UsedCells<'a, T: CellType> {
@@ -762,6 +1599,64 @@ impl<'a, T: 'a + CellType> DoubleEndedIterator for Rows<'a, T> {
 
 impl<'a, T: 'a + CellType> ExactSizeIterator for Rows<'a, T> {}
 
+/// An iterator to mutate `Range` struct row by row
+#[derive(Debug)]
+pub struct RowsMut<'a, T: CellType> {
+    inner: Option<std::slice::ChunksMut<'a, T>>,
+}
+
+impl<'a, T: 'a + CellType> Iterator for RowsMut<'a, T> {
+    type Item = &'a mut [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut().and_then(|c| c.next())
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |ch| ch.size_hint())
+    }
+}
+
+impl<'a, T: 'a + CellType> DoubleEndedIterator for RowsMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut().and_then(|c| c.next_back())
+    }
+}
+
+impl<'a, T: 'a + CellType> ExactSizeIterator for RowsMut<'a, T> {}
+
+/// An iterator to read `Range` struct column by column
+#[derive(Debug)]
+pub struct Columns<'a, T: CellType> {
+    width: usize,
+    inner: &'a [T],
+    cols: std::ops::Range<usize>,
+}
+
+impl<'a, T: 'a + CellType> Columns<'a, T> {
+    fn column(&self, col: usize) -> std::iter::StepBy<std::slice::Iter<'a, T>> {
+        self.inner[col..].iter().step_by(self.width)
+    }
+}
+
+impl<'a, T: 'a + CellType> Iterator for Columns<'a, T> {
+    type Item = std::iter::StepBy<std::slice::Iter<'a, T>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cols.next().map(|c| self.column(c))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cols.size_hint()
+    }
+}
+
+impl<'a, T: 'a + CellType> DoubleEndedIterator for Columns<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cols.next_back().map(|c| self.column(c))
+    }
+}
+
+impl<'a, T: 'a + CellType> ExactSizeIterator for Columns<'a, T> {}
+
 /// Struct with the key elements of a table
 pub struct Table<T>
 where
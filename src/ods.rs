@@ -15,7 +15,7 @@ use zip::read::{ZipArchive, ZipFile};
 use zip::result::ZipError;
 
 use crate::vba::VbaProject;
-use crate::{DataType, Metadata, Range, Reader};
+use crate::{DataType, Metadata, Range, Reader, SheetVisible};
 use std::marker::PhantomData;
 
 const MIMETYPE: &[u8] = b"application/vnd.oasis.opendocument.spreadsheet";
@@ -134,10 +134,13 @@ impl<RS: Read + Seek> Reader for Ods<RS> {
             sheet_names,
             defined_names,
         } = parse_content(zip)?;
-        let metadata = Metadata {
-            sheets: sheet_names,
+        let mut metadata = Metadata {
             names: defined_names,
+            ..Metadata::default()
         };
+        for (name, visible) in sheet_names {
+            metadata.push_sheet(name, visible);
+        }
 
         Ok(Ods {
             marker: PhantomData,
@@ -176,7 +179,7 @@ impl<RS: Read + Seek> Reader for Ods<RS> {
 
 struct Content {
     sheets: HashMap<String, (Range<DataType>, Range<String>)>,
-    sheet_names: Vec<String>,
+    sheet_names: Vec<(String, SheetVisible)>,
     defined_names: Vec<(String, String)>,
 }
 
@@ -198,9 +201,31 @@ fn parse_content<RS: Read + Seek>(mut zip: ZipArchive<RS>) -> Result<Content, Od
     let mut sheets = HashMap::new();
     let mut defined_names = Vec::new();
     let mut sheet_names = Vec::new();
+    let mut hidden_styles = Vec::new();
     loop {
         match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.name() == b"style:style" => {
+                if let Some(a) = e
+                    .attributes()
+                    .filter_map(|a| a.ok())
+                    .find(|a| a.key == b"style:name")
+                {
+                    if is_hidden_table_style(&mut reader)? {
+                        hidden_styles.push(a.value.into_owned());
+                    }
+                }
+            }
             Ok(Event::Start(ref e)) if e.name() == b"table:table" => {
+                let visible = match e
+                    .attributes()
+                    .filter_map(|a| a.ok())
+                    .find(|a| a.key == b"table:style-name")
+                {
+                    Some(ref a) if hidden_styles.iter().any(|s| *s == *a.value) => {
+                        SheetVisible::Hidden
+                    }
+                    _ => SheetVisible::Visible,
+                };
                 if let Some(ref a) = e
                     .attributes()
                     .filter_map(|a| a.ok())
@@ -210,7 +235,7 @@ fn parse_content<RS: Read + Seek>(mut zip: ZipArchive<RS>) -> Result<Content, Od
                         .unescape_and_decode_value(&reader)
                         .map_err(OdsError::Xml)?;
                     let (range, formulas) = read_table(&mut reader)?;
-                    sheet_names.push(name.clone());
+                    sheet_names.push((name.clone(), visible));
                     sheets.insert(name, (range, formulas));
                 }
             }
@@ -230,6 +255,27 @@ fn parse_content<RS: Read + Seek>(mut zip: ZipArchive<RS>) -> Result<Content, Od
     })
 }
 
+/// Reads a `style:style` node and checks whether it hides a table
+fn is_hidden_table_style(reader: &mut OdsReader<'_>) -> Result<bool, OdsError> {
+    let mut buf = Vec::new();
+    let mut hidden = false;
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.name() == b"style:table-properties" => {
+                hidden |= e
+                    .attributes()
+                    .filter_map(|a| a.ok())
+                    .any(|a| a.key == b"table:display" && &*a.value == b"false");
+            }
+            Ok(Event::End(ref e)) if e.name() == b"style:style" => return Ok(hidden),
+            Ok(Event::Eof) => return Err(OdsError::Eof("style:style")),
+            Err(e) => return Err(OdsError::Xml(e)),
+            Ok(_) => (),
+        }
+        buf.clear();
+    }
+}
+
 fn read_table(reader: &mut OdsReader<'_>) -> Result<(Range<DataType>, Range<String>), OdsError> {
     let mut cells = Vec::new();
     let mut formulas = Vec::new();
@@ -13,8 +13,9 @@ macro_rules! from_err {
 }
 
 /// Converts a &[u8] into an iterator of `u32`s
+///
+/// Trailing bytes (if `s.len()` is not a multiple of 4) are ignored
 pub fn to_u32(s: &[u8]) -> impl ExactSizeIterator<Item = u32> + '_ {
-    assert_eq!(s.len() % 4, 0);
     s.chunks_exact(4)
         .map(|data| u32::from_le_bytes(data.try_into().unwrap()))
 }
@@ -50,16 +51,17 @@ pub fn read_f64(s: &[u8]) -> f64 {
 }
 
 /// Push literal column into a String buffer
-pub fn push_column(mut col: u32, buf: &mut String) {
+pub fn push_column(col: u32, buf: &mut String) {
     if col < 26 {
         buf.push((b'A' + col as u8) as char);
     } else {
+        // bijective base 26: A..Z, AA..ZZ, AAA..
         let mut rev = String::new();
-        while col >= 26 {
-            let c = col % 26;
+        let mut col = col + 1;
+        while col > 0 {
+            let c = (col - 1) % 26;
             rev.push((b'A' + c as u8) as char);
-            col -= c;
-            col /= 26;
+            col = (col - 1) / 26;
         }
         buf.extend(rev.chars().rev());
     }
@@ -1058,5 +1060,9 @@ mod tests {
             to_u32(data).collect::<Vec<_>>(),
             [u32::from_le_bytes(*b"ABCD"), u32::from_le_bytes(*b"EFGH")]
         );
+        assert_eq!(to_u32(b"ABCDEF").len(), 1);
+        // truncated buffers (e.g. from a corrupted file) must not panic
+        assert_eq!(to_u32(b"ABC").len(), 0);
+        assert_eq!(to_u32(b"").len(), 0);
     }
 }
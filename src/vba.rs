@@ -8,10 +8,10 @@ use std::io::Read;
 use std::path::PathBuf;
 
 use byteorder::{LittleEndian, ReadBytesExt};
+use encoding_rs::UTF_16LE;
 use log::{debug, log_enabled, warn, Level};
 
 use crate::cfb::{Cfb, XlsEncoding};
-use crate::utils::read_u16;
 
 /// A VBA specific error enum
 #[derive(Debug)]
@@ -79,12 +79,47 @@ pub struct VbaProject {
     references: Vec<Reference>,
     modules: HashMap<String, Vec<u8>>,
     encoding: XlsEncoding,
+    info: ProjectInfo,
+    streams: Vec<String>,
+    vba_project_header: Option<Vec<u8>>,
+    forms: Vec<Form>,
+}
+
+/// A UserForm of a vba project
+///
+/// The designer streams are kept raw, as described in MS-OFORMS.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Form {
+    /// Form name, which is also the name of its code module
+    pub name: String,
+    /// `\x03VBFrame` stream: designer properties of the form (caption, size...) as text
+    pub vb_frame: Vec<u8>,
+    /// `f` stream: properties of the form and list of its controls
+    pub f: Vec<u8>,
+    /// `o` stream: data of the controls (e.g. text box contents), empty if there is none
+    pub o: Vec<u8>,
+}
+
+/// Information about a vba project
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectInfo {
+    /// Project name
+    pub name: String,
+    /// Project description
+    pub doc_string: String,
+    /// Path to the project help file
+    pub help_file: String,
+    /// Conditional compilation constants
+    pub constants: String,
+    /// Code page used to encode the project strings
+    pub code_page: u16,
 }
 
 impl VbaProject {
     /// Create a new `VbaProject` out of the vbaProject.bin `ZipFile` or xls file
     ///
     /// Starts reading project metadata (header, directories, sectors and minisectors).
+    /// `len` is the length of the whole container, see `Cfb::new`.
     pub fn new<R: Read>(r: &mut R, len: usize) -> Result<VbaProject, VbaError> {
         let mut cfb = Cfb::new(r, len)?;
         VbaProject::from_cfb(r, &mut cfb)
@@ -97,8 +132,8 @@ impl VbaProject {
         let stream = crate::cfb::decompress_stream(&*stream)?;
         let stream = &mut &*stream;
 
-        // read dir information record (not used)
-        let encoding = read_dir_information(stream)?;
+        // read dir information record
+        let (encoding, info) = read_dir_information(stream)?;
 
         // array of REFERENCE records
         let refs = Reference::from_stream(stream, &encoding)?;
@@ -106,23 +141,81 @@ impl VbaProject {
         // modules
         let mods: Vec<Module> = read_modules(stream, &encoding)?;
 
+        // designer modules (e.g. UserForms) are only listed in the PROJECT stream, their
+        // designer storage is named after the module stream
+        let designers = cfb
+            .get_stream("PROJECT", r)
+            .map(|project| read_designer_names(&project, &encoding))
+            .unwrap_or_default();
+        let mut forms = Vec::with_capacity(designers.len());
+        for name in designers {
+            let storage = mods
+                .iter()
+                .find(|m| m.name == name)
+                .map_or(&*name, |m| &*m.stream_name)
+                .to_string();
+            if !cfb.has_storage(&storage) {
+                continue;
+            }
+            let mut read = |stream| match cfb.get_storage_stream(&storage, stream, r) {
+                Err(crate::cfb::CfbError::StreamNotFound(_)) => Ok(Vec::new()),
+                s => s,
+            };
+            forms.push(Form {
+                vb_frame: read("\u{3}VBFrame")?,
+                f: read("f")?,
+                o: read("o")?,
+                name,
+            });
+        }
+
         // read all modules
         let modules: HashMap<String, Vec<u8>> = mods
             .into_iter()
             .map(|m| {
-                cfb.get_stream(&m.stream_name, r).and_then(|s| {
-                    crate::cfb::decompress_stream(&s[m.text_offset..]).map(move |s| (m.name, s))
-                })
+                let s = cfb.get_stream(&m.stream_name, r)?;
+                let s = s.get(m.text_offset..).ok_or_else(unexpected_eof)?;
+                Ok((m.name, crate::cfb::decompress_stream(s)?))
             })
-            .collect::<Result<HashMap<_, _>, _>>()?;
+            .collect::<Result<HashMap<_, _>, VbaError>>()?;
+
+        // _VBA_PROJECT header, the performance cache which follows is not kept
+        let vba_project_header = cfb.get_stream("_VBA_PROJECT", r).ok().map(|mut s| {
+            s.truncate(7);
+            s
+        });
 
         Ok(VbaProject {
             references: refs,
             modules,
             encoding,
+            info,
+            streams: cfb.directory_names(),
+            vba_project_header,
+            forms,
         })
     }
 
+    /// Gets the project information (name, description etc.)
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, open_workbook, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    /// let mut xl: Xlsx<_> = open_workbook(path).expect("Cannot find excel file");
+    /// let vba = xl.vba_project().unwrap().unwrap();
+    /// assert_eq!(vba.info().name, "VBAProject");
+    /// ```
+    pub fn info(&self) -> &ProjectInfo {
+        &self.info
+    }
+
+    /// Gets the project name
+    pub fn project_name(&self) -> &str {
+        &self.info.name
+    }
+
     /// Gets the list of `Reference`s
     pub fn get_references(&self) -> &[Reference] {
         &self.references
@@ -133,6 +226,68 @@ impl VbaProject {
         self.modules.keys().map(|k| &**k).collect()
     }
 
+    /// Gets the names of all the streams and storages of the project file
+    ///
+    /// Unlike `get_module_names`, this includes entries which are not parsed by calamine,
+    /// such as `_VBA_PROJECT` or `__SRP_0`.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, open_workbook, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    /// let mut xl: Xlsx<_> = open_workbook(path).expect("Cannot find excel file");
+    /// let vba = xl.vba_project().unwrap().unwrap();
+    /// assert!(vba.stream_names().iter().any(|s| s == "_VBA_PROJECT"));
+    /// ```
+    pub fn stream_names(&self) -> Vec<String> {
+        self.streams.clone()
+    }
+
+    /// Gets the reserved signature (expected to be `0x61CC`) and the version
+    /// read from the `_VBA_PROJECT` stream header
+    ///
+    /// The version identifies the VBA implementation which wrote the performance cache
+    /// and can be used to tell VBA6 and VBA7 projects apart.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, open_workbook, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    /// let mut xl: Xlsx<_> = open_workbook(path).expect("Cannot find excel file");
+    /// let vba = xl.vba_project().unwrap().unwrap();
+    /// let (signature, _version) = vba.vba_version().unwrap();
+    /// assert_eq!(signature, 0x61CC);
+    /// ```
+    pub fn vba_version(&self) -> Result<(u16, u16), VbaError> {
+        let header = self
+            .vba_project_header
+            .as_ref()
+            .ok_or_else(|| crate::cfb::CfbError::StreamNotFound("_VBA_PROJECT".to_string()))?;
+        let header = &mut &**header;
+        let signature = header.read_u16::<LittleEndian>()?;
+        let version = header.read_u16::<LittleEndian>()?;
+        Ok((signature, version))
+    }
+
+    /// Gets the UserForms of the project, with their raw designer streams
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, open_workbook, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/vba_form.xlsm", env!("CARGO_MANIFEST_DIR"));
+    /// let mut xl: Xlsx<_> = open_workbook(path).expect("Cannot find excel file");
+    /// let vba = xl.vba_project().unwrap().unwrap();
+    /// let form = &vba.forms()[0];
+    /// assert_eq!(form.name, "UserForm1");
+    /// assert!(form.vb_frame.starts_with(b"VERSION 5.00"));
+    /// ```
+    pub fn forms(&self) -> &[Form] {
+        &self.forms
+    }
+
     /// Reads module content and tries to convert to utf8
     ///
     /// While it works most of the time, the modules are MBCS encoding and the conversion
@@ -171,6 +326,23 @@ impl VbaProject {
     }
 }
 
+/// The kind of a vba reference
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum ReferenceKind {
+    /// Registered type library (REFERENCEREGISTERED)
+    Registered,
+    /// Another vba project (REFERENCEPROJECT)
+    Project,
+    /// Twiddled type library of an ActiveX control (REFERENCECONTROL)
+    Control,
+}
+
+impl Default for ReferenceKind {
+    fn default() -> ReferenceKind {
+        ReferenceKind::Registered
+    }
+}
+
 /// A vba reference
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct Reference {
@@ -180,6 +352,8 @@ pub struct Reference {
     pub description: String,
     /// location of the reference
     pub path: PathBuf,
+    /// kind of reference
+    pub kind: ReferenceKind,
 }
 
 impl Reference {
@@ -197,6 +371,7 @@ impl Reference {
             name: "".to_string(),
             description: "".to_string(),
             path: "".into(),
+            kind: ReferenceKind::default(),
         };
 
         loop {
@@ -220,19 +395,22 @@ impl Reference {
                         name: name.clone(),
                         description: name,
                         path: "".into(),
+                        kind: ReferenceKind::default(),
                     };
                     check_variable_record(0x003E, stream)?; // unicode
                 }
                 0x0033 => {
                     // REFERENCEORIGINAL (followed by REFERENCECONTROL)
+                    reference.kind = ReferenceKind::Control;
                     reference.set_libid(stream, encoding)?;
                 }
                 0x002F => {
                     // REFERENCECONTROL
-                    *stream = &stream[4..]; // SizeTwiddled: len of total ref control
+                    reference.kind = ReferenceKind::Control;
+                    skip(stream, 4)?; // SizeTwiddled: len of total ref control
                     reference.set_libid(stream, encoding)?;
 
-                    *stream = &stream[6..];
+                    skip(stream, 6)?;
                     match stream.read_u16::<LittleEndian>()? {
                         0x0016 => {
                             // optional name record extended
@@ -248,19 +426,21 @@ impl Reference {
                             });
                         }
                     }
-                    *stream = &stream[4..];
+                    skip(stream, 4)?;
                     reference.set_libid(stream, encoding)?;
-                    *stream = &stream[26..];
+                    skip(stream, 26)?;
                 }
                 0x000D => {
                     // REFERENCEREGISTERED
-                    *stream = &stream[4..];
+                    reference.kind = ReferenceKind::Registered;
+                    skip(stream, 4)?;
                     reference.set_libid(stream, encoding)?;
-                    *stream = &stream[6..];
+                    skip(stream, 6)?;
                 }
                 0x000E => {
                     // REFERENCEPROJECT
-                    *stream = &stream[4..];
+                    reference.kind = ReferenceKind::Project;
+                    skip(stream, 4)?;
                     let absolute = read_variable_record(stream, 1)?; // project libid absolute
                     {
                         let absolute = encoding.decode_all(absolute, None);
@@ -271,7 +451,7 @@ impl Reference {
                         };
                     }
                     read_variable_record(stream, 1)?; // project libid relative
-                    *stream = &stream[6..];
+                    skip(stream, 6)?;
                 }
                 c => {
                     return Err(VbaError::Unknown {
@@ -316,44 +496,56 @@ struct Module {
     text_offset: usize,
 }
 
-fn read_dir_information(stream: &mut &[u8]) -> Result<XlsEncoding, VbaError> {
+fn read_dir_information(stream: &mut &[u8]) -> Result<(XlsEncoding, ProjectInfo), VbaError> {
     debug!("read dir header");
 
     // PROJECTSYSKIND, PROJECTLCID and PROJECTLCIDINVOKE Records
-    *stream = &stream[30..];
+    skip(stream, 30)?;
 
     // PROJECT Codepage
-    let encoding = XlsEncoding::from_codepage(read_u16(&stream[6..8]))?;
-    *stream = &stream[8..];
+    skip(stream, 6)?;
+    let code_page = stream.read_u16::<LittleEndian>()?;
+    let encoding = XlsEncoding::from_codepage(code_page)?;
 
     // PROJECTNAME Record
-    check_variable_record(0x0004, stream)?;
+    let name = check_variable_record(0x0004, stream)?;
+    let name = encoding.decode_all(name, None);
 
     // PROJECTDOCSTRING Record
     check_variable_record(0x0005, stream)?;
-    check_variable_record(0x0040, stream)?; // unicode
+    let doc_string = check_variable_record(0x0040, stream)?; // unicode
+    let doc_string = UTF_16LE.decode(doc_string).0.into_owned();
 
     // PROJECTHELPFILEPATH Record - MS-OVBA 2.3.4.2.1.7
-    check_variable_record(0x0006, stream)?;
+    let help_file = check_variable_record(0x0006, stream)?;
+    let help_file = encoding.decode_all(help_file, None);
     check_variable_record(0x003D, stream)?;
 
     // PROJECTHELPCONTEXT PROJECTLIBFLAGS and PROJECTVERSION Records
-    *stream = &stream[32..];
+    skip(stream, 32)?;
 
     // PROJECTCONSTANTS Record
     check_variable_record(0x000C, stream)?;
-    check_variable_record(0x003C, stream)?; // unicode
-
-    Ok(encoding)
+    let constants = check_variable_record(0x003C, stream)?; // unicode
+    let constants = UTF_16LE.decode(constants).0.into_owned();
+
+    let info = ProjectInfo {
+        name,
+        doc_string,
+        help_file,
+        constants,
+        code_page,
+    };
+    Ok((encoding, info))
 }
 
 fn read_modules(stream: &mut &[u8], encoding: &XlsEncoding) -> Result<Vec<Module>, VbaError> {
     debug!("read all modules metadata");
-    *stream = &stream[4..];
+    skip(stream, 4)?;
 
     let module_len = stream.read_u16::<LittleEndian>()? as usize;
 
-    *stream = &stream[8..]; // PROJECTCOOKIE record
+    skip(stream, 8)?; // PROJECTCOOKIE record
     let mut modules = Vec::with_capacity(module_len);
 
     for _ in 0..module_len {
@@ -372,16 +564,16 @@ fn read_modules(stream: &mut &[u8], encoding: &XlsEncoding) -> Result<Vec<Module
 
         // offset
         check_record(0x0031, stream)?;
-        *stream = &stream[4..];
+        skip(stream, 4)?;
         let offset = stream.read_u32::<LittleEndian>()? as usize;
 
         // help context
         check_record(0x001E, stream)?;
-        *stream = &stream[8..];
+        skip(stream, 8)?;
 
         // cookie
         check_record(0x002C, stream)?;
-        *stream = &stream[6..];
+        skip(stream, 6)?;
 
         match stream.read_u16::<LittleEndian>()? {
             0x0021 /* procedural module */ |
@@ -390,7 +582,7 @@ fn read_modules(stream: &mut &[u8], encoding: &XlsEncoding) -> Result<Vec<Module
         }
 
         loop {
-            *stream = &stream[4..]; // reserved
+            skip(stream, 4)?; // reserved
             match stream.read_u16::<LittleEndian>() {
                 Ok(0x0025) /* readonly */ | Ok(0x0028) /* private */ => (),
                 Ok(0x002B) => break,
@@ -398,7 +590,7 @@ fn read_modules(stream: &mut &[u8], encoding: &XlsEncoding) -> Result<Vec<Module
                 Err(e) => return Err(VbaError::Io(e)),
             }
         }
-        *stream = &stream[4..]; // reserved
+        skip(stream, 4)?; // reserved
 
         modules.push(Module {
             name,
@@ -410,16 +602,46 @@ fn read_modules(stream: &mut &[u8], encoding: &XlsEncoding) -> Result<Vec<Module
     Ok(modules)
 }
 
+/// Reads the names of the designer modules (`BaseClass=` lines) of the PROJECT stream
+fn read_designer_names(project: &[u8], encoding: &XlsEncoding) -> Vec<String> {
+    encoding
+        .decode_all(project, None)
+        .lines()
+        .take_while(|l| !l.starts_with('['))
+        .filter(|l| l.starts_with("BaseClass="))
+        .map(|l| l["BaseClass=".len()..].trim().to_string())
+        .collect()
+}
+
 /// Reads a variable length record
 ///
 /// `mult` is a multiplier of the length (e.g 2 when parsing XLWideString)
 fn read_variable_record<'a>(r: &mut &'a [u8], mult: usize) -> Result<&'a [u8], VbaError> {
     let len = r.read_u32::<LittleEndian>()? as usize * mult;
+    if len > r.len() {
+        return Err(unexpected_eof());
+    }
     let (read, next) = r.split_at(len);
     *r = next;
     Ok(read)
 }
 
+/// Advances the stream by `len` bytes
+///
+/// Lengths and offsets come from the file itself and cannot be trusted: fails instead of
+/// panicking if the stream is too short
+fn skip(r: &mut &[u8], len: usize) -> Result<(), VbaError> {
+    if len > r.len() {
+        return Err(unexpected_eof());
+    }
+    *r = &r[len..];
+    Ok(())
+}
+
+fn unexpected_eof() -> VbaError {
+    VbaError::Io(std::io::ErrorKind::UnexpectedEof.into())
+}
+
 /// Check that next record matches `id` and returns a variable length record
 fn check_variable_record<'a>(id: u16, r: &mut &'a [u8]) -> Result<&'a [u8], VbaError> {
     check_record(id, r)?;
@@ -448,3 +670,75 @@ fn check_record(id: u16, r: &mut &[u8]) -> Result<(), VbaError> {
         Ok(())
     }
 }
+
+#[test]
+fn test_truncated_records() {
+    // variable record announcing more bytes than available
+    let mut stream: &[u8] = &[0xFF, 0xFF, 0, 0, 1, 2, 3];
+    assert!(read_variable_record(&mut stream, 1).is_err());
+
+    let mut stream: &[u8] = &[3, 0, 0, 0, 1, 2, 3, 4];
+    assert_eq!(read_variable_record(&mut stream, 1).unwrap(), [1, 2, 3]);
+    assert_eq!(stream, [4]);
+
+    // dir stream too short for its fixed size records
+    let mut stream: &[u8] = &[0; 20];
+    assert!(read_dir_information(&mut stream).is_err());
+    let mut stream: &[u8] = &[0; 4];
+    assert!(read_modules(&mut stream, &XlsEncoding::from_codepage(1252).unwrap()).is_err());
+}
+
+#[test]
+fn test_corrupted_project() {
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut data = Vec::new();
+    zip.by_name("xl/vbaProject.bin")
+        .unwrap()
+        .read_to_end(&mut data)
+        .unwrap();
+    assert!(VbaProject::new(&mut &*data, data.len()).is_ok());
+
+    // must fail gracefully, not panic
+    for len in (0..data.len()).step_by(61) {
+        let _ = VbaProject::new(&mut &data[..len], len);
+    }
+    for i in (0..data.len()).step_by(7) {
+        let mut corrupted = data.clone();
+        corrupted[i] ^= 0xFF;
+        let _ = VbaProject::new(&mut &*corrupted, corrupted.len());
+    }
+}
+
+#[test]
+fn test_reference_kinds() {
+    fn record(id: u16, data: &[u8], out: &mut Vec<u8>) {
+        out.extend_from_slice(&id.to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+    }
+    let mut stream = Vec::new();
+    // REFERENCENAME
+    record(0x0016, b"Other", &mut stream);
+    record(0x003E, b"O\0t\0h\0e\0r\0", &mut stream);
+    // REFERENCEPROJECT: size, libid absolute, libid relative, major, minor
+    let mut project = Vec::new();
+    let libid = b"*\\CC:\\other.xlsm";
+    project.extend_from_slice(&(libid.len() as u32).to_le_bytes());
+    project.extend_from_slice(libid);
+    project.extend_from_slice(&[0; 4 + 6]);
+    record(0x000E, &project, &mut stream);
+    stream.extend_from_slice(&0x000Fu16.to_le_bytes());
+
+    let encoding = XlsEncoding::from_codepage(1252).unwrap();
+    let references = Reference::from_stream(&mut &*stream, &encoding).unwrap();
+    assert_eq!(
+        references,
+        [Reference {
+            name: "Other".to_string(),
+            description: "Other".to_string(),
+            path: "C:\\other.xlsm".into(),
+            kind: ReferenceKind::Project,
+        }]
+    );
+}
@@ -10,7 +10,7 @@ use log::debug;
 use crate::cfb::{Cfb, XlsEncoding};
 use crate::utils::{push_column, read_f64, read_i32, read_u16, read_u32};
 use crate::vba::VbaProject;
-use crate::{Cell, CellErrorType, DataType, Metadata, Range, Reader};
+use crate::{Cell, CellErrorType, DataType, Metadata, Range, Reader, SheetVisible};
 
 #[derive(Debug)]
 /// An enum to handle Xls specific errors
@@ -207,8 +207,8 @@ impl<RS: Read + Seek> Xls<RS> {
                     }
                     // RRTabId
                     0x0085 => {
-                        let (pos, name) = parse_sheet_name(&mut r, &mut encoding)?;
-                        self.metadata.sheets.push(name.clone());
+                        let (pos, name, visible) = parse_sheet_name(&mut r, &mut encoding)?;
+                        self.metadata.push_sheet(name.clone(), visible);
                         sheet_names.push((pos, name)); // BoundSheet8
                     }
                     0x0018 => {
@@ -271,7 +271,11 @@ impl<RS: Read + Seek> Xls<RS> {
                     // 512: Dimensions
                     0x0200 => {
                         let Dimensions { start, end } = parse_dimensions(r.data)?;
-                        cells.reserve(((end.0 - start.0 + 1) * (end.1 - start.1 + 1)) as usize);
+                        let len = crate::Dimensions::new(start, end).len();
+                        if len < 1_000_000 {
+                            // the dimension is only a hint and may be invalid
+                            cells.reserve(len as usize);
+                        }
                     }
                     //0x0201 => cells.push(parse_blank(r.data)?), // 513: Blank
                     0x0203 => cells.push(parse_number(r.data)?), // 515: Number
@@ -318,8 +322,13 @@ impl<RS: Read + Seek> Xls<RS> {
 fn parse_sheet_name(
     r: &mut Record<'_>,
     encoding: &mut XlsEncoding,
-) -> Result<(usize, String), XlsError> {
+) -> Result<(usize, String, SheetVisible), XlsError> {
     let pos = read_u32(r.data) as usize;
+    let visible = match r.data[4] & 0b0011 {
+        1 => SheetVisible::Hidden,
+        2 => SheetVisible::VeryHidden,
+        _ => SheetVisible::Visible,
+    };
     r.data = &r.data[6..];
     let name = parse_short_string(r, encoding)?;
     let sheet_name = name
@@ -329,7 +338,7 @@ fn parse_sheet_name(
         .filter(|b| *b != 0)
         .collect::<Vec<_>>();
     let sheet_name = String::from_utf8(sheet_name).unwrap();
-    Ok((pos, sheet_name))
+    Ok((pos, sheet_name, visible))
 }
 
 fn parse_number(r: &[u8]) -> Result<Cell<DataType>, XlsError> {
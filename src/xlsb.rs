@@ -14,7 +14,7 @@ use zip::result::ZipError;
 
 use crate::utils::{push_column, read_f64, read_i32, read_u16, read_u32, read_usize};
 use crate::vba::VbaProject;
-use crate::{Cell, CellErrorType, DataType, Metadata, Range, Reader};
+use crate::{Cell, CellErrorType, DataType, Dimensions, Metadata, Range, Reader, SheetVisible};
 
 /// A Xlsb specific error
 #[derive(Debug)]
@@ -114,9 +114,18 @@ where
     sheets: Vec<(String, String)>,
     strings: Vec<String>,
     metadata: Metadata,
+    /// Vba project, parsed on first access
+    vba: Option<VbaProject>,
 }
 
 impl<RS: Read + Seek> Xlsb<RS> {
+    /// Parses the vba project again, instead of returning the one cached by
+    /// `Reader::vba_project`
+    pub fn reload_vba(&mut self) -> Option<Result<Cow<'_, VbaProject>, XlsbError>> {
+        self.vba = None;
+        self.vba_project()
+    }
+
     /// MS-XLSB
     fn read_relationships(&mut self) -> Result<HashMap<Vec<u8>, String>, XlsbError> {
         let mut relationships = HashMap::new();
@@ -226,7 +235,12 @@ impl<RS: Read + Seek> Xlsb<RS> {
                         let relid = UTF_16LE.decode(relid).0;
                         let path = format!("xl/{}", relationships[relid.as_bytes()]);
                         let name = wide_str(&buf[12 + rel_len..len], &mut 0)?;
-                        self.metadata.sheets.push(name.to_string());
+                        let visible = match read_u32(&buf[..4]) {
+                            1 => SheetVisible::Hidden,
+                            2 => SheetVisible::VeryHidden,
+                            _ => SheetVisible::Visible,
+                        };
+                        self.metadata.push_sheet(name.to_string(), visible);
                         self.sheets.push((name.into_owned(), path));
                     }
                 }
@@ -301,7 +315,7 @@ impl<RS: Read + Seek> Xlsb<RS> {
             &mut buf,
         )?;
         let (start, end) = parse_dimensions(&buf[..16]);
-        let len = (end.0 - start.0 + 1) * (end.1 - start.1 + 1);
+        let len = Dimensions::new(start, end).len();
         let mut cells = if len < 1_000_000 {
             Vec::with_capacity(len as usize)
         } else {
@@ -404,7 +418,7 @@ impl<RS: Read + Seek> Xlsb<RS> {
             &mut buf,
         )?;
         let (start, end) = parse_dimensions(&buf[..16]);
-        let len = (end.0 - start.0 + 1) * (end.1 - start.1 + 1);
+        let len = Dimensions::new(start, end).len();
         let mut cells = if len < 1_000_000 {
             Vec::with_capacity(len as usize)
         } else {
@@ -488,6 +502,7 @@ impl<RS: Read + Seek> Reader for Xlsb<RS> {
             strings: Vec::new(),
             extern_sheets: Vec::new(),
             metadata: Metadata::default(),
+            vba: None,
         };
         xlsb.read_shared_strings()?;
         let relationships = xlsb.read_relationships()?;
@@ -497,12 +512,15 @@ impl<RS: Read + Seek> Reader for Xlsb<RS> {
     }
 
     fn vba_project(&mut self) -> Option<Result<Cow<'_, VbaProject>, XlsbError>> {
-        self.zip.by_name("xl/vbaProject.bin").ok().map(|mut f| {
+        if self.vba.is_none() {
+            let mut f = self.zip.by_name("xl/vbaProject.bin").ok()?;
             let len = f.size() as usize;
-            VbaProject::new(&mut f, len)
-                .map(Cow::Owned)
-                .map_err(XlsbError::Vba)
-        })
+            match VbaProject::new(&mut f, len) {
+                Ok(vba) => self.vba = Some(vba),
+                Err(e) => return Some(Err(XlsbError::Vba(e))),
+            }
+        }
+        self.vba.as_ref().map(|vba| Ok(Cow::Borrowed(vba)))
     }
 
     fn metadata(&self) -> &Metadata {
@@ -1,7 +1,12 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
-use std::io::BufReader;
-use std::io::{Read, Seek};
+use std::cell::RefCell;
+use std::cmp::{max, min};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
 use std::str::FromStr;
 
 use log::warn;
@@ -11,17 +16,28 @@ use quick_xml::Reader as XmlReader;
 use zip::read::{ZipArchive, ZipFile};
 use zip::result::ZipError;
 
+use crate::datatype::DataTypeRef;
+use crate::utils::push_column;
 use crate::vba::VbaProject;
-use crate::{Cell, CellErrorType, DataType, Metadata, Range, Reader, Table};
+use crate::{
+    Cell, CellErrorType, DataType, DateSystem, Dimensions, Metadata, Range, Reader, SheetType,
+    SheetVisible, Table,
+};
 
 type XlsReader<'a> = XmlReader<BufReader<ZipFile<'a>>>;
 
+/// Shared formulas of a worksheet by `si` index: master cell position and formula
+type SharedFormulas = HashMap<Vec<u8>, ((u32, u32), String)>;
+
 /// Maximum number of rows allowed in an xlsx file
 pub const MAX_ROWS: u32 = 1_048_576;
 
 /// Maximum number of columns allowed in an xlsx file
 pub const MAX_COLUMNS: u32 = 16_384;
 
+/// Number of rows between two calls of the `worksheet_range_with_progress` callback
+const PROGRESS_ROWS: usize = 1_000;
+
 /// An enum for Xlsx specific errors
 #[derive(Debug)]
 pub enum XlsxError {
@@ -39,6 +55,8 @@ pub enum XlsxError {
     ParseFloat(std::num::ParseFloatError),
     /// ParseInt error
     ParseInt(std::num::ParseIntError),
+    /// ParseBool error
+    ParseBool(std::str::ParseBoolError),
 
     /// Unexpected end of xml
     XmlEof(&'static str),
@@ -50,6 +68,12 @@ pub enum XlsxError {
     Alphanumeric(u8),
     /// Numeric column
     NumericColumn(u8),
+    /// Cell reference without (or with a zero) row component, e.g. `A`
+    RangeWithoutRowComponent(String),
+    /// Cell reference without column component, e.g. `5`
+    RangeWithoutColumnComponent(String),
+    /// Cell reference beyond the maximum row (`MAX_ROWS`) or column (`XFD`)
+    RangeOutOfBounds(String),
     /// Wrong dimension count
     DimensionCount(usize),
     /// Cell 't' attribute error
@@ -60,6 +84,16 @@ pub enum XlsxError {
     Unexpected(&'static str),
     /// Cell error
     CellError(String),
+    /// Workbook is a binary (xlsb) workbook
+    BinaryWorkbook,
+    /// Workbook is encrypted (password protected)
+    Encrypted,
+    /// Xml part encoded in UTF-16 (or UTF-32), only ASCII compatible encodings are supported
+    UnsupportedEncoding(String),
+    /// Sheet without cells (e.g. a `chartsheet`), see `Sheet::typ`
+    NotAWorksheet(String),
+    /// Corrupted (e.g. truncated) zip archive
+    Corrupted(&'static str),
 }
 
 from_err!(std::io::Error, XlsxError, Io);
@@ -69,6 +103,7 @@ from_err!(quick_xml::Error, XlsxError, Xml);
 from_err!(std::string::ParseError, XlsxError, Parse);
 from_err!(std::num::ParseFloatError, XlsxError, ParseFloat);
 from_err!(std::num::ParseIntError, XlsxError, ParseInt);
+from_err!(std::str::ParseBoolError, XlsxError, ParseBool);
 
 impl std::fmt::Display for XlsxError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -80,6 +115,7 @@ impl std::fmt::Display for XlsxError {
             XlsxError::Parse(e) => write!(f, "Parse string error: {}", e),
             XlsxError::ParseInt(e) => write!(f, "Parse integer error: {}", e),
             XlsxError::ParseFloat(e) => write!(f, "Parse float error: {}", e),
+            XlsxError::ParseBool(e) => write!(f, "Parse boolean error: {}", e),
 
             XlsxError::XmlEof(e) => write!(f, "Unexpected end of xml, expecting '</{}>'", e),
             XlsxError::UnexpectedNode(e) => write!(f, "Expecting '{}' node", e),
@@ -92,6 +128,21 @@ impl std::fmt::Display for XlsxError {
                 "Numeric character is not allowed for column name, got {}",
                 e
             ),
+            XlsxError::RangeWithoutRowComponent(e) => {
+                write!(f, "Invalid cell reference '{}': missing row component", e)
+            }
+            XlsxError::RangeWithoutColumnComponent(e) => {
+                write!(
+                    f,
+                    "Invalid cell reference '{}': missing column component",
+                    e
+                )
+            }
+            XlsxError::RangeOutOfBounds(e) => write!(
+                f,
+                "Invalid cell reference '{}': beyond maximum row ({}) or column (XFD)",
+                e, MAX_ROWS
+            ),
             XlsxError::DimensionCount(e) => {
                 write!(f, "Range dimension must be lower than 2. Got {}", e)
             }
@@ -99,6 +150,22 @@ impl std::fmt::Display for XlsxError {
             XlsxError::CellRAttribute => write!(f, "Cell missing 'r' attribute"),
             XlsxError::Unexpected(e) => write!(f, "{}", e),
             XlsxError::CellError(e) => write!(f, "Unsupported cell error value '{}'", e),
+            XlsxError::BinaryWorkbook => {
+                write!(
+                    f,
+                    "Workbook is a binary (xlsb) workbook, use `Xlsb` reader instead"
+                )
+            }
+            XlsxError::Encrypted => write!(f, "Workbook is encrypted (password protected)"),
+            XlsxError::UnsupportedEncoding(p) => write!(
+                f,
+                "Part '{}' is UTF-16 (or UTF-32) encoded, only ASCII compatible encodings (e.g. UTF-8, Shift_JIS) are supported",
+                p
+            ),
+            XlsxError::NotAWorksheet(typ) => {
+                write!(f, "Sheet is a '{}' without any cell, not a worksheet", typ)
+            }
+            XlsxError::Corrupted(e) => write!(f, "Corrupted workbook: {}", e),
         }
     }
 }
@@ -113,6 +180,7 @@ impl std::error::Error for XlsxError {
             XlsxError::Parse(e) => Some(e),
             XlsxError::ParseInt(e) => Some(e),
             XlsxError::ParseFloat(e) => Some(e),
+            XlsxError::ParseBool(e) => Some(e),
             _ => None,
         }
     }
@@ -138,6 +206,93 @@ impl FromStr for CellErrorType {
 enum CellFormat {
     Other,
     Date,
+    TimeDelta,
+}
+
+/// Shared strings table
+///
+/// Strings are either all decoded when the table is read or, with
+/// `XlsxOptions::lazy_shared_strings`, only indexed and decoded when first accessed.
+enum SharedStrings {
+    /// All strings of the table
+    Decoded(Vec<String>),
+    /// Strings indexed in the raw table
+    Indexed(Box<IndexedStrings>),
+}
+
+/// Shared strings table indexed to be decoded on demand
+struct IndexedStrings {
+    /// Raw xml of the table
+    xml: Vec<u8>,
+    /// Offset of each `<si>` element in `xml`
+    offsets: Vec<usize>,
+    /// Strings decoded so far, by index
+    cache: RefCell<HashMap<usize, String>>,
+}
+
+impl Default for SharedStrings {
+    fn default() -> Self {
+        SharedStrings::Decoded(Vec::new())
+    }
+}
+
+impl SharedStrings {
+    /// number of strings in the table
+    fn len(&self) -> usize {
+        match self {
+            SharedStrings::Decoded(strings) => strings.len(),
+            SharedStrings::Indexed(indexed) => indexed.offsets.len(),
+        }
+    }
+
+    /// get the string at index `idx`, decoding it if needed
+    ///
+    /// Indexed strings are cloned out of the cache, which can only be borrowed temporarily.
+    fn get(&self, idx: usize) -> Result<Option<Cow<'_, str>>, XlsxError> {
+        match self {
+            SharedStrings::Decoded(strings) => Ok(strings.get(idx).map(|s| Cow::Borrowed(&**s))),
+            SharedStrings::Indexed(indexed) => {
+                let IndexedStrings {
+                    xml,
+                    offsets,
+                    cache,
+                } = &**indexed;
+                let offset = match offsets.get(idx) {
+                    Some(&offset) => offset,
+                    None => return Ok(None),
+                };
+                if let Some(s) = cache.borrow().get(&idx) {
+                    return Ok(Some(Cow::Owned(s.clone())));
+                }
+                let s = decode_shared_string(&xml[offset..])?;
+                cache.borrow_mut().insert(idx, s.clone());
+                Ok(Some(Cow::Owned(s)))
+            }
+        }
+    }
+
+    /// get the string at index `idx`, decoding it if needed, borrowed from the table
+    fn get_mut(&mut self, idx: usize) -> Result<Option<&str>, XlsxError> {
+        match self {
+            SharedStrings::Decoded(strings) => Ok(strings.get(idx).map(|s| &**s)),
+            SharedStrings::Indexed(indexed) => {
+                let IndexedStrings {
+                    xml,
+                    offsets,
+                    cache,
+                } = &mut **indexed;
+                let offset = match offsets.get(idx) {
+                    Some(&offset) => offset,
+                    None => return Ok(None),
+                };
+                let s = match cache.get_mut().entry(idx) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => e.insert(decode_shared_string(&xml[offset..])?),
+                };
+                Ok(Some(&**s))
+            }
+        }
+    }
 }
 
 /// A struct representing xml zipped excel file
@@ -148,43 +303,495 @@ where
 {
     zip: ZipArchive<RS>,
     /// Shared strings
-    strings: Vec<String>,
+    strings: SharedStrings,
+    /// Whether the shared strings have been read (reading them may be deferred)
+    shared_strings_read: bool,
+    /// Path of the shared strings part
+    shared_strings_path: String,
     /// Sheets paths
     sheets: Vec<(String, String)>,
     /// Tables: Name, Sheet, Columns, Data dimensions
     tables: Option<Vec<(String, String, Vec<String>, Dimensions)>>,
     /// Cell (number) formats
     formats: Vec<CellFormat>,
+    /// Cell number format codes, with the same indexing as `formats`
+    format_codes: Vec<String>,
     /// Metadata
     metadata: Metadata,
+    /// Defined names scope (index of the sheet for sheet-scoped names)
+    names_scope: Vec<Option<usize>>,
+    /// Date system, as defined in the workbook
+    date_system: DateSystem,
+    /// Calculation properties
+    calc_properties: CalcProperties,
+    /// Index of the sheet selected when the workbook was saved
+    active_tab: Option<usize>,
+    /// Parsing options
+    options: XlsxOptions,
+    /// Vba project, parsed on first access
+    vba: Option<VbaProject>,
+    /// Ranges already returned by `worksheet_range`, if `XlsxOptions::cache_ranges` is set
+    range_cache: HashMap<String, Range<DataType>>,
+}
+
+/// Options to control how a `Xlsx` workbook is read
+///
+/// # Examples
+/// ```
+/// use calamine::{DataType, Reader, Xlsx, XlsxOptions};
+///
+/// # let path = format!("{}/tests/date.xlsx", env!("CARGO_MANIFEST_DIR"));
+/// let mut workbook = Xlsx::open_with_options(path, XlsxOptions::new().convert_dates(false))
+///     .unwrap();
+/// let range = workbook.worksheet_range_at(0).unwrap().unwrap();
+/// assert_eq!(range.get_value((0, 0)), Some(&DataType::Float(44197.)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct XlsxOptions {
+    convert_dates: bool,
+    lenient: bool,
+    date_system: Option<DateSystem>,
+    buffer_capacity: usize,
+    lazy_shared_strings: bool,
+    cache_ranges: bool,
+    ignore_sheet_case: bool,
+}
+
+impl Default for XlsxOptions {
+    fn default() -> Self {
+        XlsxOptions {
+            convert_dates: true,
+            lenient: false,
+            date_system: None,
+            buffer_capacity: 8 * 1024,
+            lazy_shared_strings: false,
+            cache_ranges: false,
+            ignore_sheet_case: false,
+        }
+    }
+}
+
+impl XlsxOptions {
+    /// Constructs the default options
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Decide whether numbers formatted as dates (resp. elapsed times) are read as
+    /// `DataType::DateTime` (resp. `DataType::Duration`) (default) or as plain `DataType::Float`
+    pub fn convert_dates(&mut self, yes: bool) -> &mut Self {
+        self.convert_dates = yes;
+        self
+    }
+
+    /// Decide whether cells whose value cannot be parsed make the whole worksheet reading
+    /// fail (default) or are silently read as empty cells
+    ///
+    /// Use `Xlsx::worksheet_range_lenient` to know which cells could not be parsed.
+    pub fn lenient(&mut self, yes: bool) -> &mut Self {
+        self.lenient = yes;
+        self
+    }
+
+    /// Overrides the date system defined in the workbook, as returned by `Xlsx::date_system`
+    pub fn date_system(&mut self, date_system: DateSystem) -> &mut Self {
+        self.date_system = Some(date_system);
+        self
+    }
+
+    /// Sets the capacity of the buffer used to read each (decompressed) xml part of the
+    /// archive, defaults to 8 KiB
+    ///
+    /// Larger buffers (e.g. 64 KiB) mean fewer calls to the decompressor, which can speed up
+    /// reading large worksheets and shared strings.
+    pub fn buffer_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    /// Decide whether all shared strings are decoded when opening the workbook (default) or
+    /// only indexed when first needed and decoded when first accessed
+    ///
+    /// Lazy shared strings avoid any upfront cost on open and only scan the table, without
+    /// decoding it, when reading the values of a worksheet for the first time: this makes the
+    /// first rows of workbooks with large string tables available much sooner, and the memory
+    /// only holds the strings actually used, decoded once and cached, along with the raw table.
+    /// In exchange, each access to a shared string is slower and `worksheet_range_ref` returns
+    /// them as owned `DataTypeRef::String`.
+    ///
+    /// Tables not encoded in UTF-8 are still fully decoded when first needed.
+    pub fn lazy_shared_strings(&mut self, yes: bool) -> &mut Self {
+        self.lazy_shared_strings = yes;
+        self
+    }
+
+    /// Decide whether ranges returned by `worksheet_range` are kept in memory so that
+    /// requesting the same worksheet again returns a copy instead of parsing it again
+    /// (default: no)
+    ///
+    /// Use `Xlsx::invalidate_range_cache` to release the cached ranges.
+    pub fn cache_ranges(&mut self, yes: bool) -> &mut Self {
+        self.cache_ranges = yes;
+        self
+    }
+
+    /// Decide whether sheet names are matched case-insensitively (e.g. `sheet1` for `Sheet1`)
+    /// when reading a sheet by its name (default: no)
+    ///
+    /// A sheet whose name matches exactly is always preferred. Sheets still missing are
+    /// reported as `None`, use `Reader::try_worksheet_range` to get an error listing the sheet
+    /// names of the workbook instead.
+    pub fn ignore_sheet_case(&mut self, yes: bool) -> &mut Self {
+        self.ignore_sheet_case = yes;
+        self
+    }
+}
+
+/// Document properties of a workbook, as found in `docProps/core.xml` and `docProps/app.xml`
+///
+/// Dates are kept as written in the workbook (ISO 8601, e.g. `2016-10-18T10:19:50Z`).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DocProperties {
+    /// Title
+    pub title: Option<String>,
+    /// Subject
+    pub subject: Option<String>,
+    /// Creator (author)
+    pub creator: Option<String>,
+    /// Keywords
+    pub keywords: Option<String>,
+    /// Description (comments)
+    pub description: Option<String>,
+    /// Category
+    pub category: Option<String>,
+    /// User who last modified the workbook
+    pub last_modified_by: Option<String>,
+    /// Creation date
+    pub created: Option<String>,
+    /// Last modification date
+    pub modified: Option<String>,
+    /// Application which created the workbook
+    pub application: Option<String>,
+    /// Company
+    pub company: Option<String>,
+    /// Manager
+    pub manager: Option<String>,
+}
+
+/// State of a worksheet pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneState {
+    /// Panes are split, but not frozen (default)
+    Split,
+    /// Panes are frozen, they cannot be resized
+    Frozen,
+    /// Panes are frozen, and were split before being frozen
+    FrozenSplit,
+}
+
+impl Default for PaneState {
+    fn default() -> PaneState {
+        PaneState::Split
+    }
+}
+
+/// Pane of the first view of a worksheet, as found in `<sheetView><pane .../></sheetView>`
+///
+/// For frozen panes, `x_split` and `y_split` are the number of frozen columns and rows.
+/// For split (not frozen) panes, they are the position of the split, in twentieths of a point.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Pane {
+    /// Horizontal position of the split
+    pub x_split: f64,
+    /// Vertical position of the split
+    pub y_split: f64,
+    /// Top left visible cell of the bottom right pane (absolute position)
+    pub top_left_cell: Option<(u32, u32)>,
+    /// Pane state
+    pub state: PaneState,
+}
+
+/// Calculation mode of a workbook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalcMode {
+    /// Formulas are recalculated automatically (default)
+    Auto,
+    /// Formulas are recalculated automatically, except data tables
+    AutoNoTable,
+    /// Formulas are only recalculated on demand
+    Manual,
+}
+
+impl Default for CalcMode {
+    fn default() -> CalcMode {
+        CalcMode::Auto
+    }
+}
+
+/// Calculation properties of a workbook, as found in `<calcPr>`
+///
+/// Cells values are the values cached when the workbook was last saved, they may be stale
+/// when the calculation is manual or when the producer asks for a full calculation on load.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CalcProperties {
+    /// Calculation mode
+    pub mode: CalcMode,
+    /// Whether all formulas must be recalculated when the workbook is opened
+    pub full_calc_on_load: bool,
+}
+
+/// Column widths, row heights, hidden and outlined (grouped) columns and rows of a worksheet,
+/// as found in `<sheetFormatPr>`, `<cols>` and the attributes of `<row>`
+///
+/// Column widths are in number of characters of the default font, row heights in points.
+/// Columns and rows are indexed from 0 and only those with an explicit size (resp. hidden,
+/// resp. with a non zero outline level) are present.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SheetFormat {
+    /// Default column width, if defined
+    pub default_column_width: Option<f64>,
+    /// Default row height, if defined
+    pub default_row_height: Option<f64>,
+    /// Column widths, by column index
+    pub column_widths: BTreeMap<u32, f64>,
+    /// Row heights, by row index
+    pub row_heights: BTreeMap<u32, f64>,
+    /// Hidden columns
+    pub hidden_columns: BTreeSet<u32>,
+    /// Hidden rows
+    pub hidden_rows: BTreeSet<u32>,
+    /// Column outline levels, by column index
+    pub column_outline_levels: BTreeMap<u32, u8>,
+    /// Row outline levels, by row index
+    pub row_outline_levels: BTreeMap<u32, u8>,
+}
+
+/// Conditional formatting of a worksheet, as found in `<conditionalFormatting>` nodes
+///
+/// Only the rules are parsed, the differential formats (`dxfId`) they apply are not.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConditionalFormat {
+    /// Cell ranges the rules apply to (absolute positions)
+    pub ranges: Vec<Dimensions>,
+    /// Rules, in document order
+    pub rules: Vec<ConditionalFormatRule>,
+}
+
+/// Data validation of cells of a worksheet, as found in `<dataValidation>` nodes
+///
+/// For `list` validations (dropdowns), `formula1` is either a quoted, comma separated list of
+/// the allowed values (e.g. `"Yes,No"`) or a reference to the cells holding them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DataValidation {
+    /// Cell ranges the validation applies to (absolute positions)
+    pub ranges: Vec<Dimensions>,
+    /// Validation type (e.g. `list`, `whole`, `decimal`, `date`, `textLength`, `custom`),
+    /// `none` if not defined
+    pub typ: String,
+    /// Operator of the validation (e.g. `between`, `greaterThan`), if defined
+    pub operator: Option<String>,
+    /// First formula, the only one for most operators
+    pub formula1: Option<String>,
+    /// Second formula, for `between` and `notBetween` operators
+    pub formula2: Option<String>,
+}
+
+/// Definition of a pivot table cache, as found in `pivotCacheDefinition` parts
+///
+/// Only the source of the cache and its fields are read, the cached records are not.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PivotCacheInfo {
+    /// Type of the source (e.g. `worksheet`, `external`, `consolidation`)
+    pub source_type: String,
+    /// Worksheet holding the source data, if defined
+    pub sheet: Option<String>,
+    /// Source cell range (absolute positions), if defined
+    pub range: Option<Dimensions>,
+    /// Defined name or table used as source instead of a cell range, if any
+    pub name: Option<String>,
+    /// Names of the cached fields, in order
+    pub fields: Vec<String>,
+}
+
+/// A rule of a `ConditionalFormat`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConditionalFormatRule {
+    /// Rule type (e.g. `cellIs`, `expression`, `colorScale`, `dataBar`)
+    pub typ: String,
+    /// Operator of `cellIs` rules (e.g. `greaterThan`, `between`)
+    pub operator: Option<String>,
+    /// Priority, rules with lower values are evaluated first
+    pub priority: Option<i32>,
+    /// Formulas, without leading `=` (`between` rules have two)
+    pub formulas: Vec<String>,
+}
+
+impl Xlsx<BufReader<File>> {
+    /// Opens a workbook with custom `XlsxOptions`
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &XlsxOptions,
+    ) -> Result<Self, XlsxError> {
+        Xlsx::with_options(BufReader::new(File::open(path)?), options)
+    }
 }
 
 impl<RS: Read + Seek> Xlsx<RS> {
-    fn read_shared_strings(&mut self) -> Result<(), XlsxError> {
-        let mut xml = match xml_reader(&mut self.zip, "xl/sharedStrings.xml") {
-            None => return Ok(()),
-            Some(x) => x?,
+    /// Creates a new `Xlsx` reader with custom `XlsxOptions`
+    pub fn with_options(mut reader: RS, options: &XlsxOptions) -> Result<Self, XlsxError> {
+        // encrypted workbooks are not zip archives but ole containers
+        if crate::cfb::is_encrypted_package(&mut reader)? {
+            return Err(XlsxError::Encrypted);
+        }
+        let is_zip = starts_with_zip_signature(&mut reader)?;
+        let zip = match ZipArchive::new(reader) {
+            Ok(zip) => zip,
+            // the file starts like a zip archive but its central directory, at the very end,
+            // cannot be read: the file has most likely been truncated
+            Err(ZipError::InvalidArchive(_)) if is_zip => {
+                return Err(XlsxError::Corrupted("truncated archive"))
+            }
+            Err(e) => return Err(e.into()),
         };
-        let mut buf = Vec::new();
-        loop {
-            buf.clear();
-            match xml.read_event(&mut buf) {
-                Ok(Event::Start(ref e)) if e.local_name() == b"si" => {
-                    if let Some(s) = read_string(&mut xml, e.name())? {
-                        self.strings.push(s);
-                    }
-                }
-                Ok(Event::End(ref e)) if e.local_name() == b"sst" => break,
-                Ok(Event::Eof) => return Err(XlsxError::XmlEof("sst")),
-                Err(e) => return Err(XlsxError::Xml(e)),
-                _ => (),
+        Xlsx::from_archive_with_options(zip, options)
+    }
+
+    /// Creates a new `Xlsx` reader out of an already opened zip archive
+    ///
+    /// The archive must be a `zip::ZipArchive` of the `zip` version calamine depends on.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, Xlsx};
+    /// use std::fs::File;
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut zip = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+    /// assert!(zip.by_name("xl/workbook.xml").is_ok());
+    /// let mut workbook = Xlsx::from_archive(zip).unwrap();
+    /// assert!(workbook.worksheet_range("issue2").is_some());
+    /// ```
+    pub fn from_archive(zip: ZipArchive<RS>) -> Result<Self, XlsxError> {
+        Xlsx::from_archive_with_options(zip, &XlsxOptions::default())
+    }
+
+    /// Creates a new `Xlsx` reader with custom `XlsxOptions` out of an already opened
+    /// zip archive
+    pub fn from_archive_with_options(
+        mut zip: ZipArchive<RS>,
+        options: &XlsxOptions,
+    ) -> Result<Self, XlsxError> {
+        // the central directory may be valid while the entries it lists are not (e.g.
+        // an archive truncated and then padded), check them all before reading any part
+        for i in 0..zip.len() {
+            match zip.by_index_raw(i) {
+                Ok(_) => (),
+                Err(ZipError::InvalidArchive(e)) => return Err(XlsxError::Corrupted(e)),
+                Err(ZipError::Io(_)) => return Err(XlsxError::Corrupted("truncated archive")),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let mut xlsx = Xlsx {
+            zip,
+            strings: SharedStrings::default(),
+            shared_strings_read: false,
+            shared_strings_path: "xl/sharedStrings.xml".to_string(),
+            formats: Vec::new(),
+            format_codes: Vec::new(),
+            sheets: Vec::new(),
+            tables: None,
+            metadata: Metadata::default(),
+            names_scope: Vec::new(),
+            date_system: DateSystem::V1900,
+            calc_properties: CalcProperties::default(),
+            active_tab: None,
+            options: options.clone(),
+            vba: None,
+            range_cache: HashMap::new(),
+        };
+        let workbook_path = xlsx.read_workbook_path();
+        let relationships = xlsx.read_relationships(&workbook_path)?;
+        let mut styles_path = "xl/styles.xml".to_string();
+        for rel in relationships.values() {
+            if rel.kind.ends_with(b"/sharedStrings") {
+                xlsx.shared_strings_path = rel_target_path(&workbook_path, &rel.target);
+            } else if rel.kind.ends_with(b"/styles") {
+                styles_path = rel_target_path(&workbook_path, &rel.target);
             }
         }
+        if !options.lazy_shared_strings {
+            xlsx.read_shared_strings()?;
+        }
+        xlsx.read_styles(&styles_path)?;
+        xlsx.read_workbook(&workbook_path, &relationships)?;
+        Ok(xlsx)
+    }
+
+    /// read the shared strings table, if not already read
+    ///
+    /// The table is only indexed if `XlsxOptions::lazy_shared_strings` is set.
+    fn read_shared_strings(&mut self) -> Result<(), XlsxError> {
+        if self.shared_strings_read {
+            return Ok(());
+        }
+        let path = &self.shared_strings_path;
+        self.strings = if !self.options.lazy_shared_strings {
+            match xml_reader(&mut self.zip, path, self.options.buffer_capacity) {
+                None => SharedStrings::default(),
+                Some(xml) => SharedStrings::Decoded(read_sst(&mut xml?, |xml, si| {
+                    // strings without any text are kept so indices stay aligned
+                    Ok(read_string(xml, si)?.unwrap_or_default())
+                })?),
+            }
+        } else {
+            match self.zip.by_name(path) {
+                Ok(mut f) => {
+                    let mut bytes = Vec::new();
+                    f.read_to_end(&mut bytes)?;
+                    index_shared_strings(bytes, path)?
+                }
+                Err(ZipError::FileNotFound) => SharedStrings::default(),
+                Err(e) => return Err(e.into()),
+            }
+        };
+        self.shared_strings_read = true;
         Ok(())
     }
 
-    fn read_styles(&mut self) -> Result<(), XlsxError> {
-        let mut xml = match xml_reader(&mut self.zip, "xl/styles.xml") {
+    /// find a sheet (name and path) by its name, see `XlsxOptions::ignore_sheet_case`
+    fn sheet(&self, name: &str) -> Option<&(String, String)> {
+        find_sheet(&self.sheets, name, self.options.ignore_sheet_case)
+    }
+
+    /// get the xml reader of a worksheet, along with the workbook data needed to read it
+    ///
+    /// Returns `None` if there is no such sheet. The shared strings are read first when
+    /// `values` is set, i.e. when cell values are read.
+    fn sheet_reader(
+        &mut self,
+        name: &str,
+        values: bool,
+    ) -> Option<Result<(XlsReader<'_>, SheetContext<'_>), XlsxError>> {
+        let (_, path) = self.sheet(name)?.clone();
+        if values {
+            if let Err(e) = self.read_shared_strings() {
+                return Some(Err(e));
+            }
+        }
+        let date_system = self.date_system();
+        let xml = xml_reader(&mut self.zip, &path, self.options.buffer_capacity)?;
+        let context = SheetContext {
+            strings: &self.strings,
+            formats: &self.formats,
+            format_codes: &self.format_codes,
+            options: &self.options,
+            date_system,
+        };
+        Some(xml.map(|xml| (xml, context)))
+    }
+
+    fn read_styles(&mut self, path: &str) -> Result<(), XlsxError> {
+        let mut xml = match xml_reader(&mut self.zip, path, self.options.buffer_capacity) {
             None => return Ok(()),
             Some(x) => x?,
         };
@@ -227,22 +834,34 @@ impl<RS: Read + Seek> Xlsx<RS> {
                     inner_buf.clear();
                     match xml.read_event(&mut inner_buf) {
                         Ok(Event::Start(ref e)) if e.local_name() == b"xf" => {
-                            self.formats.push(
-                                e.attributes()
-                                    .filter_map(|a| a.ok())
-                                    .find(|a| a.key == b"numFmtId")
-                                    .map_or(CellFormat::Other, |a| {
-                                        match number_formats.get(&*a.value) {
-                                            Some(fmt) if is_custom_date_format(fmt) => {
-                                                CellFormat::Date
-                                            }
-                                            None if is_builtin_date_format_id(&a.value) => {
-                                                CellFormat::Date
-                                            }
-                                            _ => CellFormat::Other,
-                                        }
-                                    }),
+                            let id = e
+                                .attributes()
+                                .filter_map(|a| a.ok())
+                                .find(|a| a.key == b"numFmtId");
+                            self.format_codes.push(
+                                id.as_ref()
+                                    .and_then(|a| {
+                                        number_formats
+                                            .get(&*a.value)
+                                            .map(|f| f.to_string())
+                                            .or_else(|| {
+                                                builtin_format_code(&a.value).map(String::from)
+                                            })
+                                    })
+                                    .unwrap_or_else(|| "General".to_string()),
                             );
+                            self.formats.push(id.map_or(CellFormat::Other, |a| {
+                                match number_formats.get(&*a.value) {
+                                    Some(fmt) if is_custom_duration_format(fmt) => {
+                                        CellFormat::TimeDelta
+                                    }
+                                    Some(fmt) if is_custom_date_format(fmt) => CellFormat::Date,
+                                    // [h]:mm:ss
+                                    None if &*a.value == b"46" => CellFormat::TimeDelta,
+                                    None if is_builtin_date_format_id(&a.value) => CellFormat::Date,
+                                    _ => CellFormat::Other,
+                                }
+                            }));
                         }
                         Ok(Event::End(ref e)) if e.local_name() == b"cellXfs" => break,
                         Ok(Event::Eof) => return Err(XlsxError::XmlEof("cellXfs")),
@@ -259,8 +878,12 @@ impl<RS: Read + Seek> Xlsx<RS> {
         Ok(())
     }
 
-    fn read_workbook(&mut self, relationships: &HashMap<Vec<u8>, String>) -> Result<(), XlsxError> {
-        let mut xml = match xml_reader(&mut self.zip, "xl/workbook.xml") {
+    fn read_workbook(
+        &mut self,
+        workbook_path: &str,
+        relationships: &HashMap<Vec<u8>, Relationship>,
+    ) -> Result<(), XlsxError> {
+        let mut xml = match xml_reader(&mut self.zip, workbook_path, self.options.buffer_capacity) {
             None => return Ok(()),
             Some(x) => x?,
         };
@@ -273,12 +896,24 @@ impl<RS: Read + Seek> Xlsx<RS> {
                 Ok(Event::Start(ref e)) if e.local_name() == b"sheet" => {
                     let mut name = String::new();
                     let mut path = String::new();
+                    let mut visible = SheetVisible::Visible;
+                    let mut typ = SheetType::Worksheet;
                     for a in e.attributes() {
                         let a = a?;
                         match a {
                             Attribute { key: b"name", .. } => {
                                 name = a.unescape_and_decode_value(&xml)?;
                             }
+                            Attribute {
+                                key: b"state",
+                                value: v,
+                            } => {
+                                visible = match &*v {
+                                    b"hidden" => SheetVisible::Hidden,
+                                    b"veryHidden" => SheetVisible::VeryHidden,
+                                    _ => SheetVisible::Visible,
+                                };
+                            }
                             Attribute {
                                 key: b"r:id",
                                 value: v,
@@ -287,23 +922,75 @@ impl<RS: Read + Seek> Xlsx<RS> {
                                 key: b"relationships:id",
                                 value: v,
                             } => {
-                                let r = &relationships[&*v][..];
-                                // target may have pre-prended "/xl/" or "xl/" path;
-                                // strip if present
-                                path = if r.starts_with("/xl/") {
-                                    r[1..].to_string()
-                                } else if r.starts_with("xl/") {
+                                let rel = &relationships[&*v];
+                                typ = if rel.kind.ends_with(b"/chartsheet") {
+                                    SheetType::ChartSheet
+                                } else if rel.kind.ends_with(b"/dialogsheet") {
+                                    SheetType::DialogSheet
+                                } else if rel.kind.ends_with(b"/xlMacrosheet")
+                                    || rel.kind.ends_with(b"/xlIntlMacrosheet")
+                                {
+                                    SheetType::MacroSheet
+                                } else {
+                                    SheetType::Worksheet
+                                };
+                                let r = &rel.target[..];
+                                // some producers write targets relative to the package
+                                // root (e.g. "xl/worksheets/sheet1.xml")
+                                path = if r.starts_with("xl/") && workbook_path.starts_with("xl/") {
                                     r.to_string()
                                 } else {
-                                    format!("xl/{}", r)
+                                    rel_target_path(workbook_path, r)
                                 };
                             }
                             _ => (),
                         }
                     }
-                    self.metadata.sheets.push(name.to_string());
+                    self.metadata.push_sheet(name.clone(), visible);
+                    if let Some(sheet) = self.metadata.sheets_metadata.last_mut() {
+                        sheet.typ = typ;
+                    }
                     self.sheets.push((name, path));
                 }
+                Ok(Event::Start(ref e)) if e.local_name() == b"workbookPr" => {
+                    if let Some(b"1") | Some(b"true") = get_attribute(e.attributes(), b"date1904")?
+                    {
+                        self.date_system = DateSystem::V1904;
+                    }
+                }
+                // only the first view is the one Excel opens
+                Ok(Event::Start(ref e))
+                    if e.local_name() == b"workbookView" && self.active_tab.is_none() =>
+                {
+                    if let Some(tab) = get_attribute(e.attributes(), b"activeTab")? {
+                        // a view attribute is not worth failing the whole workbook
+                        match xml.decode(tab).parse() {
+                            Ok(tab) => self.active_tab = Some(tab),
+                            Err(err) => warn!("ignoring invalid activeTab: {}", err),
+                        }
+                    }
+                }
+                Ok(Event::Start(ref e)) if e.local_name() == b"calcPr" => {
+                    for a in e.attributes() {
+                        match a? {
+                            Attribute {
+                                key: b"calcMode",
+                                value: v,
+                            } => {
+                                self.calc_properties.mode = match &*v {
+                                    b"manual" => CalcMode::Manual,
+                                    b"autoNoTable" => CalcMode::AutoNoTable,
+                                    _ => CalcMode::Auto,
+                                }
+                            }
+                            Attribute {
+                                key: b"fullCalcOnLoad",
+                                value: v,
+                            } => self.calc_properties.full_calc_on_load = is_true(&v),
+                            _ => (),
+                        }
+                    }
+                }
                 Ok(Event::Start(ref e)) if e.local_name() == b"definedName" => {
                     if let Some(a) = e
                         .attributes()
@@ -311,9 +998,25 @@ impl<RS: Read + Seek> Xlsx<RS> {
                         .find(|a| a.key == b"name")
                     {
                         let name = a.unescape_and_decode_value(&xml)?;
+                        let scope = match get_attribute(e.attributes(), b"localSheetId")? {
+                            Some(id) => match xml.decode(id).parse() {
+                                Ok(id) => Some(id),
+                                // a bad scope is not worth failing the whole workbook
+                                Err(err) => {
+                                    warn!(
+                                        "invalid localSheetId for defined name {}, \
+                                         considering it global: {}",
+                                        name, err
+                                    );
+                                    None
+                                }
+                            },
+                            None => None,
+                        };
                         val_buf.clear();
-                        let value = xml.read_text(b"definedName", &mut val_buf)?;
+                        let value = xml.read_text(e.name(), &mut val_buf)?;
                         defined_names.push((name, value));
+                        self.names_scope.push(scope);
                     }
                 }
                 Ok(Event::End(ref e)) if e.local_name() == b"workbook" => break,
@@ -326,116 +1029,66 @@ impl<RS: Read + Seek> Xlsx<RS> {
         Ok(())
     }
 
-    fn read_relationships(&mut self) -> Result<HashMap<Vec<u8>, String>, XlsxError> {
-        let mut xml = match xml_reader(&mut self.zip, "xl/_rels/workbook.xml.rels") {
-            None => {
-                return Err(XlsxError::FileNotFound(
-                    "xl/_rels/workbook.xml.rels".to_string(),
-                ));
-            }
-            Some(x) => x?,
+    /// get the path of the workbook part, as defined in the package relationships or,
+    /// failing that, in the content types of the package
+    ///
+    /// Defaults to `xl/workbook.xml` if it cannot be read from the package.
+    fn read_workbook_path(&mut self) -> String {
+        let rels = match xml_reader(&mut self.zip, "_rels/.rels", self.options.buffer_capacity) {
+            Some(Ok(mut xml)) => read_rels(&mut xml).unwrap_or_default(),
+            _ => Vec::new(),
         };
-        let mut relationships = HashMap::new();
-        let mut buf = Vec::new();
-        loop {
-            buf.clear();
-            match xml.read_event(&mut buf) {
-                Ok(Event::Start(ref e)) if e.local_name() == b"Relationship" => {
-                    let mut id = Vec::new();
-                    let mut target = String::new();
-                    for a in e.attributes() {
-                        match a? {
-                            Attribute {
-                                key: b"Id",
-                                value: v,
-                            } => id.extend_from_slice(&v),
-                            Attribute {
-                                key: b"Target",
-                                value: v,
-                            } => target = xml.decode(&v).into_owned(),
-                            _ => (),
-                        }
-                    }
-                    relationships.insert(id, target);
-                }
-                Ok(Event::End(ref e)) if e.local_name() == b"Relationships" => break,
-                Ok(Event::Eof) => return Err(XlsxError::XmlEof("Relationships")),
-                Err(e) => return Err(XlsxError::Xml(e)),
-                _ => (),
-            }
+        if let Some(rel) = rels
+            .into_iter()
+            .find(|r| r.kind.ends_with(b"/officeDocument") && !r.target.is_empty())
+        {
+            return rel_target_path("", &rel.target);
+        }
+        let content_types = "[Content_Types].xml";
+        match xml_reader(&mut self.zip, content_types, self.options.buffer_capacity) {
+            Some(Ok(mut xml)) => read_workbook_part_name(&mut xml).ok().and_then(|p| p),
+            _ => None,
+        }
+        .unwrap_or_else(|| "xl/workbook.xml".to_string())
+    }
+
+    fn read_relationships(
+        &mut self,
+        workbook_path: &str,
+    ) -> Result<HashMap<Vec<u8>, Relationship>, XlsxError> {
+        let rels_path = rels_path(workbook_path);
+        if workbook_path.ends_with(".bin")
+            || self.zip.by_name(&rels_path).is_err() && self.zip.by_name("xl/workbook.bin").is_ok()
+        {
+            return Err(XlsxError::BinaryWorkbook);
         }
-        Ok(relationships)
+        let mut xml = match xml_reader(&mut self.zip, &rels_path, self.options.buffer_capacity) {
+            None => return Err(XlsxError::FileNotFound(rels_path)),
+            Some(x) => x?,
+        };
+        Ok(read_rels(&mut xml)?
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect())
     }
 
     // sheets must be added before this is called!!
     fn read_table_metadata(&mut self) -> Result<(), XlsxError> {
         for (sheet_name, sheet_path) in &self.sheets {
-            let last_folder_index = sheet_path.rfind("/").expect("should be in a folder");
-            let (base_folder, file_name) = sheet_path.split_at(last_folder_index);
-            let rel_path = format!("{}/_rels{}.rels", base_folder, file_name);
-
-            let mut table_locations = Vec::new();
+            let table_locations =
+                worksheet_rels(&mut self.zip, sheet_path, self.options.buffer_capacity)?
+                    .into_iter()
+                    .filter(|r| r.kind.ends_with(b"/relationships/table") && !r.target.is_empty())
+                    .map(|r| rel_target_path(sheet_path, &r.target))
+                    .collect::<Vec<_>>();
             let mut buf = Vec::new();
-            // we need another mutable borrow of self.zip later so we enclose this borrow within braces
-            {
-                let mut xml = match xml_reader(&mut self.zip, &rel_path) {
-                    None => continue,
-                    Some(x) => x?,
-                };
-                loop {
-                    buf.clear();
-                    match xml.read_event(&mut buf) {
-                        Ok(Event::Start(ref e)) if e.local_name() == b"Relationship" => {
-                            let mut id = Vec::new();
-                            let mut target = String::new();
-                            let mut table_type = false;
-                            for a in e.attributes() {
-                                match a? {
-                                Attribute {
-                                    key: b"Id",
-                                    value: v,
-                                } => id.extend_from_slice(&v),
-                                Attribute {
-                                    key: b"Target",
-                                    value: v,
-                                } => target = xml.decode(&v).into_owned(),
-                                Attribute {
-                                    key: b"Type",
-                                    value: v,
-                                } => table_type = *v == b"http://schemas.openxmlformats.org/officeDocument/2006/relationships/table"[..],
-                                _ => (),
-                            }
-                            }
-                            if table_type {
-                                if target.starts_with("../") {
-                                    // this is an incomplete implementation, but should be good enough for excel
-                                    let new_index =
-                                        base_folder.rfind("/").expect("Must be a parent folder");
-                                    let full_path = format!(
-                                        "{}{}",
-                                        base_folder[..new_index].to_owned(),
-                                        target[2..].to_owned()
-                                    );
-                                    table_locations.push(full_path);
-                                } else if target.is_empty() { // do nothing
-                                } else {
-                                    table_locations.push(target);
-                                }
-                            }
-                        }
-                        Ok(Event::End(ref e)) if e.local_name() == b"Relationships" => break,
-                        Ok(Event::Eof) => return Err(XlsxError::XmlEof("Relationships")),
-                        Err(e) => return Err(XlsxError::Xml(e)),
-                        _ => (),
-                    }
-                }
-            }
             let mut new_tables = Vec::new();
             for table_file in table_locations {
-                let mut xml = match xml_reader(&mut self.zip, &table_file) {
-                    None => continue,
-                    Some(x) => x?,
-                };
+                let mut xml =
+                    match xml_reader(&mut self.zip, &table_file, self.options.buffer_capacity) {
+                        None => continue,
+                        Some(x) => x?,
+                    };
                 let mut column_names = Vec::new();
                 let mut table_meta = InnerTableMetadata::new();
                 loop {
@@ -568,9 +1221,1556 @@ impl<RS: Read + Seek> Xlsx<RS> {
                     data: tbl_rng,
                 }))
             }
-            Err(e) => Some(Err(e)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Get the raw (decompressed) content of any part of the workbook package
+    ///
+    /// This gives access to the parts not interpreted by calamine (custom xml, document
+    /// properties etc.). Returns `None` if there is no such part.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let workbook_xml = workbook.read_part("xl/workbook.xml").unwrap().unwrap();
+    /// assert!(workbook_xml.starts_with(b"<?xml"));
+    /// assert!(workbook.read_part("not/a/part.xml").is_none());
+    /// ```
+    pub fn read_part(&mut self, name: &str) -> Option<Result<Vec<u8>, XlsxError>> {
+        match self.zip.by_name(name) {
+            Ok(mut f) => {
+                let mut buf = Vec::with_capacity(f.size() as usize);
+                Some(f.read_to_end(&mut buf).map(|_| buf).map_err(XlsxError::Io))
+            }
+            Err(ZipError::FileNotFound) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+
+    /// Get the document properties (title, author, dates etc.) of the workbook
+    ///
+    /// Properties which are not defined are `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let properties = workbook.properties().unwrap();
+    /// assert_eq!(properties.created.as_deref(), Some("2016-10-18T10:19:50Z"));
+    /// assert_eq!(properties.application.as_deref(), Some("Microsoft Excel"));
+    /// ```
+    pub fn properties(&mut self) -> Result<DocProperties, XlsxError> {
+        let mut properties = DocProperties::default();
+        for path in &["docProps/core.xml", "docProps/app.xml"] {
+            let mut xml = match xml_reader(&mut self.zip, path, self.options.buffer_capacity) {
+                None => continue,
+                Some(x) => x?,
+            };
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match xml.read_event(&mut buf) {
+                    Ok(Event::Start(ref e)) => {
+                        let property = match e.local_name() {
+                            b"title" => &mut properties.title,
+                            b"subject" => &mut properties.subject,
+                            b"creator" => &mut properties.creator,
+                            b"keywords" => &mut properties.keywords,
+                            b"description" => &mut properties.description,
+                            b"category" => &mut properties.category,
+                            b"lastModifiedBy" => &mut properties.last_modified_by,
+                            b"created" => &mut properties.created,
+                            b"modified" => &mut properties.modified,
+                            b"Application" => &mut properties.application,
+                            b"Company" => &mut properties.company,
+                            b"Manager" => &mut properties.manager,
+                            _ => continue,
+                        };
+                        let value = xml.read_text(e.name(), &mut Vec::new())?;
+                        if !value.is_empty() {
+                            *property = Some(value);
+                        }
+                    }
+                    Ok(Event::Eof) => break,
+                    Err(e) => return Err(XlsxError::Xml(e)),
+                    _ => (),
+                }
+            }
+        }
+        Ok(properties)
+    }
+
+    /// Get the date system of the workbook
+    ///
+    /// `DataType::DateTime` values are the raw serial numbers stored in the workbook, they
+    /// must be interpreted according to this date system.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{DateSystem, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/date.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// assert_eq!(workbook.date_system(), DateSystem::V1900);
+    /// ```
+    pub fn date_system(&self) -> DateSystem {
+        self.options.date_system.unwrap_or(self.date_system)
+    }
+
+    /// Get the calculation properties of the workbook
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{CalcMode, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/calc_properties.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let calc = workbook.calc_properties();
+    /// if calc.mode == CalcMode::Manual || calc.full_calc_on_load {
+    ///     println!("cached values may be stale");
+    /// }
+    /// ```
+    pub fn calc_properties(&self) -> CalcProperties {
+        self.calc_properties
+    }
+
+    /// Get the name of the sheet selected when the workbook was last saved (`activeTab`)
+    ///
+    /// Returns `None` if the workbook doesn't define it.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{open_workbook, Reader, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let name = workbook.active_sheet().unwrap_or("Sheet1").to_string();
+    /// let range = workbook.worksheet_range(&name);
+    /// ```
+    pub fn active_sheet(&self) -> Option<&str> {
+        self.active_tab
+            .and_then(|i| self.metadata.sheets.get(i))
+            .map(|s| &**s)
+    }
+
+    /// Get the number of strings in the shared strings table
+    ///
+    /// The table is read first if it hasn't been yet (see `XlsxOptions::lazy_shared_strings`).
+    pub fn shared_string_count(&mut self) -> Result<usize, XlsxError> {
+        self.read_shared_strings()?;
+        Ok(self.strings.len())
+    }
+
+    /// Get the shared string at index `idx`, as referenced by `t="s"` cells
+    ///
+    /// Returns `None` if the index is out of bounds. Rich text strings are returned with
+    /// all their runs concatenated. Useful to check how cell indices map to parsed strings.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{open_workbook, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// for i in 0..workbook.shared_string_count().unwrap() {
+    ///     println!("{}: {:?}", i, workbook.shared_string(i).unwrap());
+    /// }
+    /// ```
+    pub fn shared_string(&mut self, idx: usize) -> Result<Option<&str>, XlsxError> {
+        self.read_shared_strings()?;
+        self.strings.get_mut(idx)
+    }
+
+    /// Get all defined names along with their scope
+    ///
+    /// Names are in the same order as `defined_names`, the last element is the name of the
+    /// sheet for sheet-scoped (local) names, or `None` for workbook (global) names.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/defined_names_scope.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// for (name, formula, scope) in workbook.defined_names_scoped() {
+    ///     match scope {
+    ///         Some(sheet) => println!("{} (local to '{}'): {}", name, sheet, formula),
+    ///         None => println!("{} (global): {}", name, formula),
+    ///     }
+    /// }
+    /// ```
+    pub fn defined_names_scoped(&self) -> Vec<(&str, &str, Option<&str>)> {
+        self.metadata
+            .names
+            .iter()
+            .zip(&self.names_scope)
+            .map(|((name, formula), scope)| {
+                let sheet = scope.and_then(|i| self.metadata.sheets.get(i).map(|s| &**s));
+                (&**name, &**formula, sheet)
+            })
+            .collect()
+    }
+
+    /// Read worksheet data in corresponding worksheet path, without building a `Range`
+    ///
+    /// `push_cell` is called with the absolute position (row, column) and the value of every
+    /// non empty cell, as soon as it is parsed.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let mut total = 0.;
+    /// workbook
+    ///     .worksheet_cells("issue2", |_pos, value| total += value.get_float().unwrap_or(0.))
+    ///     .expect("sheet not found")
+    ///     .expect("error while reading sheet");
+    /// assert_eq!(total, 6.);
+    /// ```
+    pub fn worksheet_cells<F>(
+        &mut self,
+        name: &str,
+        mut push_cell: F,
+    ) -> Option<Result<(), XlsxError>>
+    where
+        F: FnMut((u32, u32), DataType),
+    {
+        let sheet = self.sheet_reader(name, true)?;
+        Some(sheet.and_then(|(mut xml, context)| {
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match xml.read_event(&mut buf) {
+                    Ok(Event::Start(ref e)) if e.local_name() == b"sheetData" => {
+                        return read_sheet_data(&mut xml, context, &mut |pos, v| {
+                            push_cell(pos, v.into())
+                        });
+                    }
+                    Ok(Event::Eof) => return Ok(()),
+                    Err(e) => return Err(XlsxError::Xml(e)),
+                    _ => (),
+                }
+            }
+        }))
+    }
+
+    /// Get worksheet range where shared string values are only borrowed
+    ///
+    /// This avoids cloning every shared string of the workbook, which can be
+    /// significantly faster on string heavy worksheets.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{DataTypeRef, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let range = workbook
+    ///     .worksheet_range_ref("issue2")
+    ///     .expect("sheet not found")
+    ///     .expect("error while reading sheet");
+    /// assert_eq!(range.get_value((0, 1)), Some(&DataTypeRef::SharedString("a")));
+    /// ```
+    pub fn worksheet_range_ref(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Range<DataTypeRef<'_>>, XlsxError>> {
+        let sheet = self.sheet_reader(name, true)?;
+        Some(sheet.and_then(|(xml, context)| {
+            worksheet(xml, &mut |xml, cells| {
+                read_sheet_data(xml, context, &mut |pos, v| cells.push(Cell::new(pos, v)))
+            })
+        }))
+    }
+
+    /// Clears the ranges cached by `worksheet_range` when `XlsxOptions::cache_ranges` is set
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, Xlsx, XlsxOptions};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook = Xlsx::open_with_options(path, XlsxOptions::new().cache_ranges(true))
+    ///     .unwrap();
+    /// let first = workbook.worksheet_range("issue2").unwrap().unwrap();
+    /// // served from the cache
+    /// assert_eq!(workbook.worksheet_range("issue2").unwrap().unwrap(), first);
+    /// workbook.invalidate_range_cache();
+    /// // parsed again
+    /// assert_eq!(workbook.worksheet_range("issue2").unwrap().unwrap(), first);
+    /// ```
+    pub fn invalidate_range_cache(&mut self) {
+        self.range_cache.clear();
+    }
+
+    /// Get worksheet range, reporting the reading progress
+    ///
+    /// `progress` is called with the number of rows read so far (rows without any value
+    /// are not counted) every 1000 rows, then once with the total when the sheet is
+    /// completely read.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let range = workbook
+    ///     .worksheet_range_with_progress("issue2", |rows| eprintln!("{} rows read", rows))
+    ///     .expect("sheet not found")
+    ///     .expect("error while reading sheet");
+    /// ```
+    pub fn worksheet_range_with_progress<F>(
+        &mut self,
+        name: &str,
+        mut progress: F,
+    ) -> Option<Result<Range<DataType>, XlsxError>>
+    where
+        F: FnMut(usize),
+    {
+        let sheet = self.sheet_reader(name, true)?;
+        Some(sheet.and_then(|(xml, context)| {
+            worksheet(xml, &mut |xml, cells| {
+                let mut last_row = None;
+                let mut rows = 0;
+                read_sheet_data(xml, context, &mut |pos, v| {
+                    if last_row != Some(pos.0) {
+                        if rows > 0 && rows % PROGRESS_ROWS == 0 {
+                            progress(rows);
+                        }
+                        last_row = Some(pos.0);
+                        rows += 1;
+                    }
+                    cells.push(Cell::new(pos, v.into()))
+                })?;
+                progress(rows);
+                Ok(())
+            })
+        }))
+    }
+
+    /// Get the first `n` rows of a worksheet range
+    ///
+    /// Parsing stops after the `n`th row, which is much faster than reading the whole
+    /// worksheet when only its first rows are needed (e.g. to guess the type of each column).
+    /// Rows are counted as they are written in the worksheet: rows without any cell are
+    /// usually not written and are therefore not counted. The range only spans the cells
+    /// read, regardless of the worksheet dimension.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let head = workbook.worksheet_head("issue2", 2).unwrap().unwrap();
+    /// assert_eq!(head.get_size(), (2, 2));
+    /// ```
+    pub fn worksheet_head(
+        &mut self,
+        name: &str,
+        n: usize,
+    ) -> Option<Result<Range<DataType>, XlsxError>> {
+        let sheet = self.sheet_reader(name, true)?;
+        Some(sheet.and_then(|(xml, context)| {
+            worksheet(xml, &mut |xml, cells| {
+                read_sheet_data_with(
+                    xml,
+                    context,
+                    n,
+                    &mut |pos, v| cells.push(Cell::new(pos, v.into())),
+                    &mut |_, e| {
+                        if context.options.lenient {
+                            Ok(())
+                        } else {
+                            Err(e)
+                        }
+                    },
+                )
+            })
+        }))
+    }
+
+    /// Get worksheet range, skipping the cells whose value cannot be parsed
+    ///
+    /// Unlike `worksheet_range`, which fails on the first invalid cell, invalid cells are
+    /// left empty and their positions (absolute) and errors are returned alongside the range.
+    /// Errors in the worksheet structure itself (e.g. invalid xml) still fail.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let (range, errors) = workbook
+    ///     .worksheet_range_lenient("issue2")
+    ///     .expect("sheet not found")
+    ///     .expect("error while reading sheet");
+    /// assert!(errors.is_empty());
+    /// assert_eq!(range.get_size(), (3, 2));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn worksheet_range_lenient(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<(Range<DataType>, Vec<((u32, u32), XlsxError)>), XlsxError>> {
+        let sheet = self.sheet_reader(name, true)?;
+        let mut errors = Vec::new();
+        Some(sheet.and_then(|(xml, context)| {
+            let range = worksheet(xml, &mut |xml, cells| {
+                read_sheet_data_with(
+                    xml,
+                    context,
+                    std::usize::MAX,
+                    &mut |pos, v| cells.push(Cell::new(pos, v.into())),
+                    &mut |pos, e| {
+                        errors.push((pos, e));
+                        Ok(())
+                    },
+                )
+            })?;
+            Ok((range, errors))
+        }))
+    }
+
+    /// Get worksheet range, keeping only the rows matching `predicate`
+    ///
+    /// The worksheet is streamed row by row: each row is passed to `predicate` as a slice
+    /// indexed by column (`row[0]` is column `A`) and is dropped unless it returns `true`.
+    /// Rows without any cell are never kept. Kept cells retain their absolute positions and
+    /// the range only spans the kept rows.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{DataType, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let range = workbook
+    ///     .worksheet_range_filtered("issue2", |row| row[0] != DataType::Float(1.))
+    ///     .expect("sheet not found")
+    ///     .expect("error while reading sheet");
+    /// assert_eq!(range.start(), Some((1, 0)));
+    /// assert_eq!(range.get_size(), (2, 2));
+    /// ```
+    pub fn worksheet_range_filtered<F>(
+        &mut self,
+        name: &str,
+        mut predicate: F,
+    ) -> Option<Result<Range<DataType>, XlsxError>>
+    where
+        F: FnMut(&[DataType]) -> bool,
+    {
+        fn flush<F>(
+            row: &mut Vec<((u32, u32), DataType)>,
+            values: &mut Vec<DataType>,
+            predicate: &mut F,
+            cells: &mut Vec<Cell<DataType>>,
+        ) where
+            F: FnMut(&[DataType]) -> bool,
+        {
+            let width = match row.iter().map(|&((_, c), _)| c).max() {
+                Some(c) => c as usize + 1,
+                None => return,
+            };
+            // the buffer is reused across rows, missing cells are filled in bulk
+            values.clear();
+            values.resize(width, DataType::Empty);
+            for (pos, v) in row.iter() {
+                values[pos.1 as usize] = v.clone();
+            }
+            if predicate(values) {
+                cells.extend(row.drain(..).map(|(pos, v)| Cell::new(pos, v)));
+            } else {
+                row.clear();
+            }
+        }
+
+        let sheet = self.sheet_reader(name, true)?;
+        Some(sheet.and_then(|(xml, context)| {
+            worksheet(xml, &mut |xml, cells| {
+                let mut row = Vec::new();
+                let mut values = Vec::new();
+                read_sheet_data(xml, context, &mut |pos, v| {
+                    if row.first().map_or(false, |&((r, _), _)| r != pos.0) {
+                        flush(&mut row, &mut values, &mut predicate, cells);
+                    }
+                    row.push((pos, v.into()));
+                })?;
+                flush(&mut row, &mut values, &mut predicate, cells);
+                Ok(())
+            })
+        }))
+    }
+
+    /// Get the values of a block of cells, referenced like in Excel (`Sheet1!B2:D20`)
+    ///
+    /// The sheet name may be quoted (`'My sheet'!A1:B2`) and defaults to the active sheet (or
+    /// the first one) when omitted. `$` markers are ignored and a single cell (`Sheet1!C3`) is
+    /// a valid block. The returned range spans exactly the requested block, cells outside of
+    /// the used area of the sheet being empty. Whole columns (`Sheet1!A:C`) and whole rows
+    /// (`Sheet1!2:5`) only span up to the end of the used area.
+    ///
+    /// Returns `None` if the sheet doesn't exist.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{open_workbook, DataType, Xlsx};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let block = workbook.worksheet_range_str("issue2!A1:B3").unwrap().unwrap();
+    /// assert_eq!(block.get_size(), (3, 2));
+    /// assert_eq!(block.get_value((2, 1)), Some(&DataType::String("c".to_string())));
+    /// ```
+    pub fn worksheet_range_str(
+        &mut self,
+        spec: &str,
+    ) -> Option<Result<Range<DataType>, XlsxError>> {
+        let (sheet, block) = match spec.rfind('!') {
+            Some(i) => {
+                let sheet = &spec[..i];
+                let sheet = if sheet.len() > 1 && sheet.starts_with('\'') && sheet.ends_with('\'') {
+                    sheet[1..sheet.len() - 1].replace("''", "'")
+                } else {
+                    sheet.to_string()
+                };
+                (sheet, &spec[i + 1..])
+            }
+            None => {
+                let sheet = self
+                    .active_sheet()
+                    .or_else(|| self.metadata.sheets.first().map(|s| &**s))?;
+                (sheet.to_string(), spec)
+            }
+        };
+        let block = block.replace('$', "");
+        let dim = match get_dimension(block.as_bytes()) {
+            Ok(dim) => dim,
+            Err(e) => return Some(Err(e)),
+        };
+        let start = (min(dim.start.0, dim.end.0), min(dim.start.1, dim.end.1));
+        let end = (max(dim.start.0, dim.end.0), max(dim.start.1, dim.end.1));
+        self.worksheet_range(&sheet).map(|range| {
+            range.map(|range| {
+                // do not allocate a million rows for whole columns references
+                let (mut end_row, mut end_col) = end;
+                let used_end = range.end().unwrap_or((0, 0));
+                if end_row == MAX_ROWS - 1 {
+                    end_row = max(start.0, used_end.0);
+                }
+                if end_col == MAX_COLUMNS - 1 {
+                    end_col = max(start.1, used_end.1);
+                }
+                range.range(start, (end_row, end_col))
+            })
+        })
+    }
+
+    /// Get worksheet formulas, positioned like the values of `worksheet_range`
+    ///
+    /// Unlike `Reader::worksheet_formula`, which only spans the cells holding a formula, the
+    /// returned range covers every cell with a value (extended to the formulas without any
+    /// cached value) so it can be compared cell by cell with the value range.
+    /// Formulas are returned as `DataType::String`, cells without formula are `DataType::Empty`.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{DataType, Reader, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let values = workbook.worksheet_range("Sheet1").unwrap().unwrap();
+    /// let formulas = workbook.worksheet_formulas("Sheet1").unwrap().unwrap();
+    /// assert_eq!(formulas.start(), values.start());
+    /// assert_eq!(formulas.end(), values.end());
+    /// ```
+    pub fn worksheet_formulas(&mut self, name: &str) -> Option<Result<Range<DataType>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(xml, _)| {
+            worksheet(xml, &mut |xml, cells| {
+                let mut shared = SharedFormulas::new();
+                read_sheet(xml, &mut |xml, e, pos, _| {
+                    let same_cell = cells
+                        .last()
+                        .map_or(false, |c: &Cell<DataType>| c.pos == pos);
+                    match e.local_name() {
+                        b"is" | b"v" => {
+                            // only the position matters, the cell may already hold its formula
+                            let has_value = if e.local_name() == b"is" {
+                                read_string(xml, e.name())?.is_some()
+                            } else {
+                                !xml.read_text(e.name(), &mut Vec::new())?.is_empty()
+                            };
+                            if has_value && !same_cell {
+                                cells.push(Cell::new(pos, DataType::Empty));
+                            }
+                        }
+                        b"f" => {
+                            let f = read_formula(xml, e, pos, &mut shared)?;
+                            if !f.is_empty() {
+                                if same_cell {
+                                    cells.pop();
+                                }
+                                cells.push(Cell::new(pos, DataType::String(f)));
+                            }
+                        }
+                        _ => return Err(XlsxError::UnexpectedNode("v, f, or is")),
+                    }
+                    Ok(())
+                })
+            })
+        }))
+    }
+
+    /// Get the number format code (e.g. `"0.00%"`, `"#,##0"`) of each cell of a worksheet
+    ///
+    /// The range is positioned like the values of `worksheet_range`: every cell holding a value
+    /// gets its format code (`"General"` if it has no specific or a locale dependent format),
+    /// cells without value are left empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Reader, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/date.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let values = workbook.worksheet_range("Sheet1").unwrap().unwrap();
+    /// let formats = workbook.worksheet_number_formats("Sheet1").unwrap().unwrap();
+    /// assert_eq!(formats.start(), values.start());
+    /// assert_eq!(formats.end(), values.end());
+    /// ```
+    pub fn worksheet_number_formats(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Range<String>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(xml, context)| {
+            worksheet(xml, &mut |xml, cells| {
+                read_sheet(xml, &mut |xml, e, pos, c_element| {
+                    let has_value = match e.local_name() {
+                        b"is" => read_string(xml, e.name())?.is_some(),
+                        b"v" => !xml.read_text(e.name(), &mut Vec::new())?.is_empty(),
+                        b"f" => {
+                            xml.read_to_end(e.name(), &mut Vec::new())?;
+                            false
+                        }
+                        _ => return Err(XlsxError::UnexpectedNode("v, f, or is")),
+                    };
+                    if has_value {
+                        let style = match get_attribute(c_element.attributes(), b"s")? {
+                            Some(s) => std::str::from_utf8(s).unwrap_or("0").parse()?,
+                            None => 0,
+                        };
+                        let code = context.format_codes.get(style).map_or("General", |c| &**c);
+                        cells.push(Cell::new(pos, code.to_string()));
+                    }
+                    Ok(())
+                })
+            })
+        }))
+    }
+
+    /// Get the merged regions of a worksheet
+    ///
+    /// Regions are given in absolute positions. Within a merged region, only the top left cell
+    /// holds a value, all other cells are read as empty by `worksheet_range`.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Dimensions, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/merged_range.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let merged = workbook.worksheet_merge_cells("Sheet1").unwrap().unwrap();
+    /// assert_eq!(merged[0], Dimensions::new((0, 0), (0, 2)));
+    /// ```
+    pub fn worksheet_merge_cells(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Vec<Dimensions>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(mut xml, _)| read_merge_cells(&mut xml)))
+    }
+
+    /// Get the hyperlinks of a worksheet, as (absolute) cell positions and targets
+    ///
+    /// External targets (urls, files) are resolved through the worksheet relationships,
+    /// links within the workbook are returned as their location (e.g. `Sheet2!A1`).
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/hyperlinks.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let links = workbook.worksheet_hyperlinks("Sheet1").unwrap().unwrap();
+    /// assert_eq!(links[0], ((1, 1), "https://example.com/".to_string()));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn worksheet_hyperlinks(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Vec<((u32, u32), String)>, XlsxError>> {
+        let (_, path) = self.sheet(name)?.clone();
+        let relationships = match worksheet_rels(&mut self.zip, &path, self.options.buffer_capacity)
+        {
+            Ok(r) => r,
+            Err(e) => return Some(Err(e)),
+        };
+        let xml = xml_reader(&mut self.zip, &path, self.options.buffer_capacity)?;
+        Some(xml.and_then(|mut xml| read_hyperlinks(&mut xml, &relationships)))
+    }
+
+    /// Get the comments (notes) of a worksheet, as (absolute) cell positions and texts
+    ///
+    /// Rich text comments are returned as plain text, including the author name Excel
+    /// usually prepends to the comment.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/comments.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let comments = workbook.worksheet_comments("Sheet1").unwrap().unwrap();
+    /// assert_eq!(comments[0], ((0, 1), "check this value".to_string()));
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn worksheet_comments(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Vec<((u32, u32), String)>, XlsxError>> {
+        let (_, path) = self.sheet(name)?.clone();
+        let relationships = match worksheet_rels(&mut self.zip, &path, self.options.buffer_capacity)
+        {
+            Ok(r) => r,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut comments = Vec::new();
+        for rel in relationships
+            .iter()
+            .filter(|r| r.kind.ends_with(b"/relationships/comments"))
+        {
+            let comments_path = rel_target_path(&path, &rel.target);
+            match xml_reader(&mut self.zip, &comments_path, self.options.buffer_capacity) {
+                Some(Ok(mut xml)) => match read_comments(&mut xml) {
+                    Ok(c) => comments.extend(c),
+                    Err(e) => return Some(Err(e)),
+                },
+                Some(Err(e)) => return Some(Err(e)),
+                None => return Some(Err(XlsxError::FileNotFound(comments_path))),
+            }
+        }
+        Some(Ok(comments))
+    }
+
+    /// Parses the vba project again, instead of returning the one cached by
+    /// `Reader::vba_project`
+    pub fn reload_vba(&mut self) -> Option<Result<Cow<'_, VbaProject>, XlsxError>> {
+        self.vba = None;
+        self.vba_project()
+    }
+
+    /// Get the column widths and row heights of a worksheet
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/sheet_format.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let format = workbook.worksheet_format("Sheet1").unwrap().unwrap();
+    /// // column B
+    /// assert_eq!(format.column_widths.get(&1), Some(&12.5));
+    /// ```
+    pub fn worksheet_format(&mut self, name: &str) -> Option<Result<SheetFormat, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(mut xml, _)| read_sheet_format(&mut xml)))
+    }
+
+    /// Get the tab color of a sheet, as an ARGB hex string (e.g. `FFFF0000`)
+    ///
+    /// Returns `Some(Ok(None))` if the sheet has no tab color or if it is not defined as an
+    /// explicit rgb color (e.g. theme colors). Only the beginning of the sheet is read.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/tab_color.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let color = workbook.worksheet_tab_color("Red").unwrap().unwrap();
+    /// assert_eq!(color.as_deref(), Some("FFFF0000"));
+    /// ```
+    pub fn worksheet_tab_color(&mut self, name: &str) -> Option<Result<Option<String>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(mut xml, _)| read_tab_color(&mut xml)))
+    }
+
+    /// Get the pane (e.g. frozen rows and columns) of the first view of a worksheet
+    ///
+    /// Returns `Some(Ok(None))` if the worksheet has no pane.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{PaneState, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/pane.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let pane = workbook.worksheet_pane("Frozen").unwrap().unwrap().unwrap();
+    /// assert_eq!(pane.state, PaneState::Frozen);
+    /// // first row is frozen
+    /// assert_eq!(pane.y_split, 1.);
+    /// ```
+    pub fn worksheet_pane(&mut self, name: &str) -> Option<Result<Option<Pane>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(mut xml, _)| read_pane(&mut xml)))
+    }
+
+    /// Get the conditional formatting rules of a worksheet
+    ///
+    /// Conditional formats defined in the worksheet extensions (`x14:conditionalFormatting`,
+    /// e.g. data bars written by newer Excel versions) are returned as well.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Dimensions, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/conditional_formatting.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let formats = workbook.worksheet_conditional_formats("Sheet1").unwrap().unwrap();
+    /// assert_eq!(formats[0].ranges, vec![Dimensions::new((0, 0), (9, 0))]);
+    /// let rule = &formats[0].rules[0];
+    /// assert_eq!(rule.typ, "cellIs");
+    /// assert_eq!(rule.operator.as_deref(), Some("greaterThan"));
+    /// assert_eq!(rule.formulas, vec!["5".to_string()]);
+    /// ```
+    pub fn worksheet_conditional_formats(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Vec<ConditionalFormat>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(mut xml, _)| read_conditional_formats(&mut xml)))
+    }
+
+    /// Get the data validations (e.g. dropdown lists) of a worksheet
+    ///
+    /// Data validations defined in the worksheet extensions (`x14:dataValidation`, e.g.
+    /// lists referencing another sheet) are returned as well.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Dimensions, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/data_validation.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let validations = workbook.worksheet_data_validations("Sheet1").unwrap().unwrap();
+    /// assert_eq!(validations[0].typ, "list");
+    /// assert_eq!(validations[0].ranges, vec![Dimensions::new((1, 1), (9, 1))]);
+    /// assert_eq!(validations[0].formula1.as_deref(), Some("\"Yes,No\""));
+    /// ```
+    pub fn worksheet_data_validations(
+        &mut self,
+        name: &str,
+    ) -> Option<Result<Vec<DataValidation>, XlsxError>> {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(mut xml, _)| read_data_validations(&mut xml)))
+    }
+
+    /// Get the definitions of the pivot table caches of the workbook
+    ///
+    /// Pivot tables themselves are not evaluated, but their caches give the source data
+    /// of each pivot table.
+    ///
+    /// # Examples
+    /// ```
+    /// use calamine::{Dimensions, Xlsx, open_workbook};
+    ///
+    /// # let path = format!("{}/tests/pivot_cache.xlsx", env!("CARGO_MANIFEST_DIR"));
+    /// let mut workbook: Xlsx<_> = open_workbook(path).unwrap();
+    /// let caches = workbook.pivot_caches().unwrap();
+    /// assert_eq!(caches[0].sheet.as_deref(), Some("Sheet1"));
+    /// assert_eq!(caches[0].range, Some(Dimensions::new((0, 0), (2, 1))));
+    /// assert_eq!(caches[0].fields, vec!["label", "value"]);
+    /// ```
+    pub fn pivot_caches(&mut self) -> Result<Vec<PivotCacheInfo>, XlsxError> {
+        let workbook_path = self.read_workbook_path();
+        let capacity = self.options.buffer_capacity;
+        let paths = match xml_reader(&mut self.zip, &rels_path(&workbook_path), capacity) {
+            Some(xml) => read_rels(&mut xml?)?
+                .into_iter()
+                .filter(|r| r.kind.ends_with(b"/pivotCacheDefinition") && !r.target.is_empty())
+                .map(|r| rel_target_path(&workbook_path, &r.target))
+                .collect(),
+            None => Vec::new(),
+        };
+        let mut caches = Vec::with_capacity(paths.len());
+        for path in paths {
+            match xml_reader(&mut self.zip, &path, capacity) {
+                Some(xml) => caches.push(read_pivot_cache_definition(&mut xml?)?),
+                None => return Err(XlsxError::FileNotFound(path)),
+            }
+        }
+        Ok(caches)
+    }
+}
+
+/// A relationship between two parts of the package
+struct Relationship {
+    id: Vec<u8>,
+    /// relationship type (e.g. `.../relationships/hyperlink`)
+    kind: Vec<u8>,
+    target: String,
+}
+
+/// find a sheet (name and path) by its name, an exact match being preferred to a
+/// case-insensitive one
+fn find_sheet<'a>(
+    sheets: &'a [(String, String)],
+    name: &str,
+    ignore_case: bool,
+) -> Option<&'a (String, String)> {
+    sheets.iter().find(|(n, _)| n == name).or_else(|| {
+        if ignore_case {
+            let name = name.to_lowercase();
+            sheets.iter().find(|(n, _)| n.to_lowercase() == name)
+        } else {
+            None
+        }
+    })
+}
+
+/// check if the reader starts with a zip local file header signature
+///
+/// The reader is rewound to its initial position before returning.
+fn starts_with_zip_signature<R: Read + Seek>(r: &mut R) -> Result<bool, std::io::Error> {
+    let start = r.seek(SeekFrom::Current(0))?;
+    let mut signature = [0u8; 4];
+    let is_zip = r.read_exact(&mut signature).is_ok() && signature == *b"PK\x03\x04";
+    r.seek(SeekFrom::Start(start))?;
+    Ok(is_zip)
+}
+
+/// Content types of the workbook part: workbooks, templates and add-ins, with or without macros
+const WORKBOOK_CONTENT_TYPES: &[&[u8]] = &[
+    b"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml",
+    b"application/vnd.openxmlformats-officedocument.spreadsheetml.template.main+xml",
+    b"application/vnd.ms-excel.sheet.macroEnabled.main+xml",
+    b"application/vnd.ms-excel.template.macroEnabled.main+xml",
+    b"application/vnd.ms-excel.addin.macroEnabled.main+xml",
+];
+
+/// read the package content types to find the name of the workbook part
+fn read_workbook_part_name(xml: &mut XlsReader<'_>) -> Result<Option<String>, XlsxError> {
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"Override" => {
+                let (mut name, mut content_type) = (None, None);
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"PartName",
+                            value: v,
+                        } => name = Some(xml.decode(&v).into_owned()),
+                        Attribute {
+                            key: b"ContentType",
+                            value: v,
+                        } => content_type = Some(v.into_owned()),
+                        _ => (),
+                    }
+                }
+                if let (Some(name), Some(content_type)) = (name, content_type) {
+                    if WORKBOOK_CONTENT_TYPES.contains(&&*content_type) {
+                        return Ok(Some(name.trim_start_matches('/').to_string()));
+                    }
+                }
+            }
+            Ok(Event::Eof) => return Ok(None),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+}
+
+/// read a relationships part
+fn read_rels(xml: &mut XlsReader<'_>) -> Result<Vec<Relationship>, XlsxError> {
+    let mut relationships = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"Relationship" => {
+                let mut rel = Relationship {
+                    id: Vec::new(),
+                    kind: Vec::new(),
+                    target: String::new(),
+                };
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"Id",
+                            value: v,
+                        } => rel.id.extend_from_slice(&v),
+                        Attribute {
+                            key: b"Type",
+                            value: v,
+                        } => rel.kind.extend_from_slice(&v),
+                        Attribute {
+                            key: b"Target",
+                            value: v,
+                        } => rel.target = xml.decode(&v).into_owned(),
+                        _ => (),
+                    }
+                }
+                relationships.push(rel);
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"Relationships" => break,
+            Ok(Event::Eof) => return Err(XlsxError::XmlEof("Relationships")),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(relationships)
+}
+
+/// read the relationships of a worksheet, if any
+fn worksheet_rels<RS: Read + Seek>(
+    zip: &mut ZipArchive<RS>,
+    sheet_path: &str,
+    capacity: usize,
+) -> Result<Vec<Relationship>, XlsxError> {
+    match xml_reader(zip, &rels_path(sheet_path), capacity) {
+        Some(xml) => read_rels(&mut xml?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// path of the relationships part of the part `part_path`
+fn rels_path(part_path: &str) -> String {
+    match part_path.rfind('/') {
+        Some(i) => format!("{}/_rels{}.rels", &part_path[..i], &part_path[i..]),
+        None => format!("_rels/{}.rels", part_path),
+    }
+}
+
+/// resolve the target of a relationship of the part `part_path` into a path within the archive
+fn rel_target_path(part_path: &str, target: &str) -> String {
+    if target.starts_with('/') {
+        return target[1..].to_string();
+    }
+    let mut path: Vec<&str> = part_path.split('/').collect();
+    path.pop();
+    for part in target.split('/') {
+        match part {
+            ".." => {
+                path.pop();
+            }
+            "." => (),
+            p => path.push(p),
+        }
+    }
+    path.join("/")
+}
+
+/// read the comments part of a worksheet
+#[allow(clippy::type_complexity)]
+fn read_comments(xml: &mut XlsReader<'_>) -> Result<Vec<((u32, u32), String)>, XlsxError> {
+    let mut comments = Vec::new();
+    let mut pos = None;
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"comment" => {
+                pos = match get_attribute(e.attributes(), b"ref")? {
+                    Some(r) => Some(get_dimension(r)?.start),
+                    None => None,
+                };
+            }
+            Ok(Event::Start(ref e)) if e.local_name() == b"text" => {
+                let text = read_string(xml, e.name())?.unwrap_or_default();
+                if let Some(pos) = pos.take() {
+                    comments.push((pos, text));
+                }
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"comments" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(comments)
+}
+
+/// read all hyperlink nodes of a worksheet, resolving their targets with the worksheet
+/// relationships
+#[allow(clippy::type_complexity)]
+fn read_hyperlinks(
+    xml: &mut XlsReader<'_>,
+    relationships: &[Relationship],
+) -> Result<Vec<((u32, u32), String)>, XlsxError> {
+    let mut hyperlinks = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"hyperlink" => {
+                let mut pos = None;
+                let mut target = None;
+                let mut location = None;
+                for a in e.attributes() {
+                    let a = a?;
+                    match a.key {
+                        b"ref" => pos = Some(get_dimension(&a.value)?.start),
+                        b"location" => location = Some(xml.decode(&a.value).into_owned()),
+                        // relationship id, the namespace prefix is usually (but not always) 'r'
+                        k if k.ends_with(b":id") => {
+                            target = relationships
+                                .iter()
+                                .find(|r| r.id[..] == *a.value)
+                                .map(|r| &r.target)
+                        }
+                        _ => (),
+                    }
+                }
+                // internal links only have a location (e.g. "Sheet2!A1"), external
+                // ones may have both (the location being the fragment)
+                let link = match (target, location) {
+                    (Some(t), Some(l)) => format!("{}#{}", t, l),
+                    (Some(t), None) => t.clone(),
+                    (None, Some(l)) => l,
+                    (None, None) => continue,
+                };
+                if let Some(pos) = pos {
+                    hyperlinks.push((pos, link));
+                }
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"worksheet" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(hyperlinks)
+}
+
+/// read the column widths and row heights of a worksheet
+fn read_sheet_format(xml: &mut XlsReader<'_>) -> Result<SheetFormat, XlsxError> {
+    let mut format = SheetFormat::default();
+    let mut row: Option<u32> = None;
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"sheetFormatPr" => {
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"defaultColWidth",
+                            value: v,
+                        } => format.default_column_width = Some(xml.decode(&v).parse()?),
+                        Attribute {
+                            key: b"defaultRowHeight",
+                            value: v,
+                        } => format.default_row_height = Some(xml.decode(&v).parse()?),
+                        _ => (),
+                    }
+                }
+            }
+            Ok(Event::Start(ref e)) if e.local_name() == b"col" => {
+                let (mut min, mut max, mut width) = (None, None, None);
+                let (mut hidden, mut level) = (false, 0);
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"min",
+                            value: v,
+                        } => min = Some(xml.decode(&v).parse::<u32>()?),
+                        Attribute {
+                            key: b"max",
+                            value: v,
+                        } => max = Some(xml.decode(&v).parse::<u32>()?),
+                        Attribute {
+                            key: b"width",
+                            value: v,
+                        } => width = Some(xml.decode(&v).parse::<f64>()?),
+                        Attribute {
+                            key: b"hidden",
+                            value: v,
+                        } => hidden = is_true(&v),
+                        Attribute {
+                            key: b"outlineLevel",
+                            value: v,
+                        } => level = xml.decode(&v).parse()?,
+                        _ => (),
+                    }
+                }
+                if let Some(min) = min {
+                    // columns are 1-based, and limited to XFD
+                    let max = max.unwrap_or(min).min(MAX_COLUMNS);
+                    for c in min.max(1) - 1..max {
+                        if let Some(width) = width {
+                            format.column_widths.insert(c, width);
+                        }
+                        if hidden {
+                            format.hidden_columns.insert(c);
+                        }
+                        if level > 0 {
+                            format.column_outline_levels.insert(c, level);
+                        }
+                    }
+                }
+            }
+            Ok(Event::Start(ref e)) if e.local_name() == b"row" => {
+                // rows without 'r' attribute follow the previous one
+                let mut r = row.map_or(0, |r| r + 1);
+                let mut height = None;
+                let (mut hidden, mut level) = (false, 0);
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"r",
+                            value: v,
+                        } => r = xml.decode(&v).parse::<u32>()?.saturating_sub(1),
+                        Attribute {
+                            key: b"ht",
+                            value: v,
+                        } => height = Some(xml.decode(&v).parse()?),
+                        Attribute {
+                            key: b"hidden",
+                            value: v,
+                        } => hidden = is_true(&v),
+                        Attribute {
+                            key: b"outlineLevel",
+                            value: v,
+                        } => level = xml.decode(&v).parse()?,
+                        _ => (),
+                    }
+                }
+                if let Some(height) = height {
+                    format.row_heights.insert(r, height);
+                }
+                if hidden {
+                    format.hidden_rows.insert(r);
+                }
+                if level > 0 {
+                    format.row_outline_levels.insert(r, level);
+                }
+                row = Some(r);
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"sheetData" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(format)
+}
+
+/// read the tab color in the sheetPr node of a sheet
+fn read_tab_color(xml: &mut XlsReader<'_>) -> Result<Option<String>, XlsxError> {
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"tabColor" => {
+                return Ok(
+                    get_attribute(e.attributes(), b"rgb")?.map(|rgb| xml.decode(rgb).into_owned())
+                );
+            }
+            // sheetPr is the first child of the sheet, no need to read further
+            Ok(Event::End(ref e)) if e.local_name() == b"sheetPr" => return Ok(None),
+            Ok(Event::Start(ref e)) if e.local_name() == b"sheetData" => return Ok(None),
+            Ok(Event::Eof) => return Ok(None),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+}
+
+/// xml boolean attribute value
+fn is_true(v: &[u8]) -> bool {
+    v == b"1" || v == b"true"
+}
+
+/// read the pane of the first sheetView of a worksheet
+fn read_pane(xml: &mut XlsReader<'_>) -> Result<Option<Pane>, XlsxError> {
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"pane" => {
+                let mut pane = Pane::default();
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"xSplit",
+                            value: v,
+                        } => pane.x_split = xml.decode(&v).parse()?,
+                        Attribute {
+                            key: b"ySplit",
+                            value: v,
+                        } => pane.y_split = xml.decode(&v).parse()?,
+                        Attribute {
+                            key: b"topLeftCell",
+                            value: v,
+                        } => pane.top_left_cell = Some(get_row_column(&v)?),
+                        Attribute {
+                            key: b"state",
+                            value: v,
+                        } => {
+                            pane.state = match &*v {
+                                b"frozen" => PaneState::Frozen,
+                                b"frozenSplit" => PaneState::FrozenSplit,
+                                _ => PaneState::Split,
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+                return Ok(Some(pane));
+            }
+            // only the first view is considered, and views are before the data
+            Ok(Event::End(ref e)) if e.local_name() == b"sheetView" => return Ok(None),
+            Ok(Event::Start(ref e)) if e.local_name() == b"sheetData" => return Ok(None),
+            Ok(Event::Eof) => return Ok(None),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+}
+
+/// read all mergeCell nodes of a worksheet
+fn read_merge_cells(xml: &mut XlsReader<'_>) -> Result<Vec<Dimensions>, XlsxError> {
+    let mut merge_cells = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"mergeCell" => {
+                if let Some(r) = get_attribute(e.attributes(), b"ref")? {
+                    merge_cells.push(get_dimension(r)?);
+                }
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"worksheet" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(merge_cells)
+}
+
+/// read all conditionalFormatting nodes of a worksheet
+fn read_conditional_formats(xml: &mut XlsReader<'_>) -> Result<Vec<ConditionalFormat>, XlsxError> {
+    let mut formats = Vec::new();
+    let mut buf = Vec::new();
+    let mut inner_buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"conditionalFormatting" => {
+                let mut format = ConditionalFormat::default();
+                if let Some(sqref) = get_attribute(e.attributes(), b"sqref")? {
+                    format.ranges = get_sqref(sqref)?;
+                }
+                loop {
+                    inner_buf.clear();
+                    match xml.read_event(&mut inner_buf) {
+                        Ok(Event::Start(ref e)) if e.local_name() == b"cfRule" => {
+                            let mut rule = ConditionalFormatRule::default();
+                            for a in e.attributes() {
+                                match a? {
+                                    Attribute {
+                                        key: b"type",
+                                        value: v,
+                                    } => rule.typ = xml.decode(&v).into_owned(),
+                                    Attribute {
+                                        key: b"operator",
+                                        value: v,
+                                    } => rule.operator = Some(xml.decode(&v).into_owned()),
+                                    Attribute {
+                                        key: b"priority",
+                                        value: v,
+                                    } => rule.priority = Some(xml.decode(&v).parse()?),
+                                    _ => (),
+                                }
+                            }
+                            format.rules.push(rule);
+                        }
+                        // x14 rules use `<xm:f>` instead of `<formula>`
+                        Ok(Event::Start(ref e))
+                            if e.local_name() == b"formula" || e.local_name() == b"f" =>
+                        {
+                            let formula = xml.read_text(e.name(), &mut Vec::new())?;
+                            if let Some(rule) = format.rules.last_mut() {
+                                rule.formulas.push(formula);
+                            }
+                        }
+                        // x14 conditional formats have a `<xm:sqref>` child instead of
+                        // a `sqref` attribute
+                        Ok(Event::Start(ref e)) if e.local_name() == b"sqref" => {
+                            let sqref = xml.read_text(e.name(), &mut Vec::new())?;
+                            format.ranges = get_sqref(sqref.as_bytes())?;
+                        }
+                        Ok(Event::End(ref e)) if e.local_name() == b"conditionalFormatting" => {
+                            break
+                        }
+                        Ok(Event::Eof) => return Err(XlsxError::XmlEof("conditionalFormatting")),
+                        Err(e) => return Err(XlsxError::Xml(e)),
+                        _ => (),
+                    }
+                }
+                formats.push(format);
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"worksheet" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(formats)
+}
+
+/// read all dataValidation nodes of a worksheet
+fn read_data_validations(xml: &mut XlsReader<'_>) -> Result<Vec<DataValidation>, XlsxError> {
+    let mut validations = Vec::new();
+    let mut buf = Vec::new();
+    let mut inner_buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"dataValidation" => {
+                let mut validation = DataValidation {
+                    typ: "none".to_string(),
+                    ..Default::default()
+                };
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"type",
+                            value: v,
+                        } => validation.typ = xml.decode(&v).into_owned(),
+                        Attribute {
+                            key: b"operator",
+                            value: v,
+                        } => validation.operator = Some(xml.decode(&v).into_owned()),
+                        Attribute {
+                            key: b"sqref",
+                            value: v,
+                        } => validation.ranges = get_sqref(&v)?,
+                        _ => (),
+                    }
+                }
+                // x14 formulas are wrapped in a `<xm:f>` node, collect all the texts
+                // within `<formula1>` (resp. `<formula2>`)
+                let mut formula: Option<String> = None;
+                loop {
+                    inner_buf.clear();
+                    match xml.read_event(&mut inner_buf) {
+                        Ok(Event::Start(ref e))
+                            if e.local_name() == b"formula1" || e.local_name() == b"formula2" =>
+                        {
+                            formula = Some(String::new());
+                        }
+                        Ok(Event::Text(ref t)) => {
+                            if let Some(ref mut f) = formula {
+                                f.push_str(&t.unescape_and_decode(xml)?);
+                            }
+                        }
+                        Ok(Event::End(ref e)) if e.local_name() == b"formula1" => {
+                            validation.formula1 = formula.take();
+                        }
+                        Ok(Event::End(ref e)) if e.local_name() == b"formula2" => {
+                            validation.formula2 = formula.take();
+                        }
+                        // x14 data validations have a `<xm:sqref>` child instead of
+                        // a `sqref` attribute
+                        Ok(Event::Start(ref e)) if e.local_name() == b"sqref" => {
+                            let sqref = xml.read_text(e.name(), &mut Vec::new())?;
+                            validation.ranges = get_sqref(sqref.as_bytes())?;
+                        }
+                        Ok(Event::End(ref e)) if e.local_name() == b"dataValidation" => break,
+                        Ok(Event::Eof) => return Err(XlsxError::XmlEof("dataValidation")),
+                        Err(e) => return Err(XlsxError::Xml(e)),
+                        _ => (),
+                    }
+                }
+                validations.push(validation);
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"worksheet" => break,
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    Ok(validations)
+}
+
+/// read a pivotCacheDefinition part
+fn read_pivot_cache_definition(xml: &mut XlsReader<'_>) -> Result<PivotCacheInfo, XlsxError> {
+    let mut cache = PivotCacheInfo::default();
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"cacheSource" => {
+                if let Some(v) = get_attribute(e.attributes(), b"type")? {
+                    cache.source_type = xml.decode(v).into_owned();
+                }
+            }
+            Ok(Event::Start(ref e)) if e.local_name() == b"worksheetSource" => {
+                for a in e.attributes() {
+                    match a? {
+                        Attribute {
+                            key: b"ref",
+                            value: v,
+                        } => cache.range = Some(get_dimension(&v)?),
+                        Attribute {
+                            key: b"sheet",
+                            value: v,
+                        } => cache.sheet = Some(xml.decode(&v).into_owned()),
+                        Attribute {
+                            key: b"name",
+                            value: v,
+                        } => cache.name = Some(xml.decode(&v).into_owned()),
+                        _ => (),
+                    }
+                }
+            }
+            Ok(Event::Start(ref e)) if e.local_name() == b"cacheField" => {
+                if let Some(v) = get_attribute(e.attributes(), b"name")? {
+                    cache.fields.push(xml.decode(v).into_owned());
+                }
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"pivotCacheDefinition" => break,
+            Ok(Event::Eof) => return Err(XlsxError::XmlEof("pivotCacheDefinition")),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
         }
     }
+    Ok(cache)
+}
+
+/// converts a space separated list of ranges (e.g. `A1:B2 D4`) into dimensions
+fn get_sqref(sqref: &[u8]) -> Result<Vec<Dimensions>, XlsxError> {
+    sqref
+        .split(|c| *c == b' ')
+        .filter(|r| !r.is_empty())
+        .map(get_dimension)
+        .collect()
 }
 
 struct InnerTableMetadata {
@@ -593,28 +2793,35 @@ impl InnerTableMetadata {
     }
 }
 
-fn worksheet<T, F>(
-    strings: &[String],
-    formats: &[CellFormat],
-    mut xml: XlsReader<'_>,
-    read_data: &mut F,
-) -> Result<Range<T>, XlsxError>
+/// Workbook data needed to read the cells of a worksheet, see `Xlsx::sheet_reader`
+#[derive(Clone, Copy)]
+struct SheetContext<'a> {
+    strings: &'a SharedStrings,
+    formats: &'a [CellFormat],
+    format_codes: &'a [String],
+    options: &'a XlsxOptions,
+    /// Date system of the workbook, possibly overridden by the options
+    date_system: DateSystem,
+}
+
+fn worksheet<T, F>(mut xml: XlsReader<'_>, read_data: &mut F) -> Result<Range<T>, XlsxError>
 where
     T: Default + Clone + PartialEq,
-    F: FnMut(
-        &[String],
-        &[CellFormat],
-        &mut XlsReader<'_>,
-        &mut Vec<Cell<T>>,
-    ) -> Result<(), XlsxError>,
+    F: FnMut(&mut XlsReader<'_>, &mut Vec<Cell<T>>) -> Result<(), XlsxError>,
 {
     let mut cells = Vec::new();
     let mut buf = Vec::new();
+    let mut dimension = None;
     'xml: loop {
         buf.clear();
         match xml.read_event(&mut buf) {
             Ok(Event::Start(ref e)) => {
                 match e.local_name() {
+                    typ @ b"chartsheet" | typ @ b"dialogsheet" => {
+                        return Err(XlsxError::NotAWorksheet(
+                            String::from_utf8_lossy(typ).into_owned(),
+                        ));
+                    }
                     b"dimension" => {
                         for a in e.attributes() {
                             if let Attribute {
@@ -622,7 +2829,9 @@ where
                                 value: rdim,
                             } = a?
                             {
-                                let len = get_dimension(&rdim)?.len();
+                                let dim = get_dimension(&rdim)?;
+                                let len = dim.len();
+                                dimension = Some(dim);
                                 if len < 1_000_000 {
                                     // it is unlikely to have more than that
                                     // there may be of empty cells
@@ -634,7 +2843,7 @@ where
                         return Err(XlsxError::UnexpectedNode("dimension"));
                     }
                     b"sheetData" => {
-                        read_data(&strings, &formats, &mut xml, &mut cells)?;
+                        read_data(&mut xml, &mut cells)?;
                         break;
                     }
                     _ => (),
@@ -645,7 +2854,18 @@ where
             _ => (),
         }
     }
-    Ok(Range::from_sparse(cells))
+    // the declared dimension is only a hint, the range always spans the actual cells
+    let range = Range::from_sparse(cells);
+    if let (Some(dim), Some(start), Some(end)) = (dimension, range.start(), range.end()) {
+        if !dim.contains(start.0, start.1) || !dim.contains(end.0, end.1) {
+            warn!(
+                "worksheet dimension {:?} doesn't contain all its cells {:?}",
+                dim,
+                Dimensions::new(start, end)
+            );
+        }
+    }
+    Ok(range)
 }
 
 impl<RS: Read + Seek> Reader for Xlsx<RS> {
@@ -656,28 +2876,19 @@ impl<RS: Read + Seek> Reader for Xlsx<RS> {
     where
         RS: Read + Seek,
     {
-        let mut xlsx = Xlsx {
-            zip: ZipArchive::new(reader)?,
-            strings: Vec::new(),
-            formats: Vec::new(),
-            sheets: Vec::new(),
-            tables: None,
-            metadata: Metadata::default(),
-        };
-        xlsx.read_shared_strings()?;
-        xlsx.read_styles()?;
-        let relationships = xlsx.read_relationships()?;
-        xlsx.read_workbook(&relationships)?;
-        Ok(xlsx)
+        Xlsx::with_options(reader, &XlsxOptions::default())
     }
 
     fn vba_project(&mut self) -> Option<Result<Cow<'_, VbaProject>, XlsxError>> {
-        self.zip.by_name("xl/vbaProject.bin").ok().map(|mut f| {
+        if self.vba.is_none() {
+            let mut f = self.zip.by_name("xl/vbaProject.bin").ok()?;
             let len = f.size() as usize;
-            VbaProject::new(&mut f, len)
-                .map(Cow::Owned)
-                .map_err(XlsxError::Vba)
-        })
+            match VbaProject::new(&mut f, len) {
+                Ok(vba) => self.vba = Some(vba),
+                Err(e) => return Some(Err(XlsxError::Vba(e))),
+            }
+        }
+        self.vba.as_ref().map(|vba| Ok(Cow::Borrowed(vba)))
     }
 
     fn metadata(&self) -> &Metadata {
@@ -685,34 +2896,35 @@ impl<RS: Read + Seek> Reader for Xlsx<RS> {
     }
 
     fn worksheet_range(&mut self, name: &str) -> Option<Result<Range<DataType>, XlsxError>> {
-        let xml = match self.sheets.iter().find(|&&(ref n, _)| n == name) {
-            Some(&(_, ref path)) => xml_reader(&mut self.zip, path),
-            None => return None,
-        };
-        let strings = &self.strings;
-        let formats = &self.formats;
-        xml.map(|xml| {
-            worksheet(strings, formats, xml?, &mut |s, f, xml, cells| {
-                read_sheet_data(xml, s, f, cells)
+        let (name, _) = self.sheet(name)?;
+        let name = name.clone();
+        if let Some(range) = self.range_cache.get(&name) {
+            return Some(Ok(range.clone()));
+        }
+        let sheet = self.sheet_reader(&name, true)?;
+        let range = sheet.and_then(|(xml, context)| {
+            worksheet(xml, &mut |xml, cells| {
+                read_sheet_data(xml, context, &mut |pos, v| {
+                    cells.push(Cell::new(pos, v.into()))
+                })
             })
-        })
+        });
+        if let (true, Ok(range)) = (self.options.cache_ranges, &range) {
+            self.range_cache.insert(name, range.clone());
+        }
+        Some(range)
     }
 
     fn worksheet_formula(&mut self, name: &str) -> Option<Result<Range<String>, XlsxError>> {
-        let xml = match self.sheets.iter().find(|&&(ref n, _)| n == name) {
-            Some(&(_, ref path)) => xml_reader(&mut self.zip, path),
-            None => return None,
-        };
-
-        let strings = &self.strings;
-        let formats = &self.formats;
-        xml.map(|xml| {
-            worksheet(strings, formats, xml?, &mut |_, _, xml, cells| {
-                read_sheet(xml, cells, &mut |cells, xml, e, pos, _| {
+        let sheet = self.sheet_reader(name, false)?;
+        Some(sheet.and_then(|(xml, _)| {
+            worksheet(xml, &mut |xml, cells| {
+                let mut shared = SharedFormulas::new();
+                read_sheet(xml, &mut |xml, e, pos, _| {
                     match e.local_name() {
                         b"is" | b"v" => xml.read_to_end(e.name(), &mut Vec::new())?,
                         b"f" => {
-                            let f = xml.read_text(e.name(), &mut Vec::new())?;
+                            let f = read_formula(xml, e, pos, &mut shared)?;
                             if !f.is_empty() {
                                 cells.push(Cell::new(pos, f));
                             }
@@ -722,21 +2934,20 @@ impl<RS: Read + Seek> Reader for Xlsx<RS> {
                     Ok(())
                 })
             })
-        })
+        }))
     }
 
     fn worksheets(&mut self) -> Vec<(String, Range<DataType>)> {
-        self.sheets
-            .clone()
+        let names: Vec<String> = self.sheets.iter().map(|(n, _)| n.clone()).collect();
+        names
             .into_iter()
-            .filter_map(|(name, path)| {
-                let xml = xml_reader(&mut self.zip, &path)?.ok()?;
-                let range = worksheet(
-                    &self.strings,
-                    &self.formats,
-                    xml,
-                    &mut |s, f, xml, cells| read_sheet_data(xml, s, f, cells),
-                )
+            .filter_map(|name| {
+                let (xml, context) = self.sheet_reader(&name, true)?.ok()?;
+                let range = worksheet(xml, &mut |xml, cells| {
+                    read_sheet_data(xml, context, &mut |pos, v| {
+                        cells.push(Cell::new(pos, v.into()))
+                    })
+                })
                 .ok()?;
                 Some((name, range))
             })
@@ -747,24 +2958,130 @@ impl<RS: Read + Seek> Reader for Xlsx<RS> {
 fn xml_reader<'a, RS>(
     zip: &'a mut ZipArchive<RS>,
     path: &str,
+    capacity: usize,
 ) -> Option<Result<XlsReader<'a>, XlsxError>>
 where
     RS: Read + Seek,
 {
     match zip.by_name(path) {
-        Ok(f) => {
-            let mut r = XmlReader::from_reader(BufReader::new(f));
-            r.check_end_names(false)
-                .trim_text(false)
-                .check_comments(false)
-                .expand_empty_elements(true);
-            Some(Ok(r))
-        }
+        Ok(f) => Some(xml_reader_from(BufReader::with_capacity(capacity, f), path)),
         Err(ZipError::FileNotFound) => None,
         Err(e) => Some(Err(e.into())),
     }
 }
 
+/// configure an xml reader over the content of the part at `path`
+fn xml_reader_from<B: BufRead>(mut f: B, path: &str) -> Result<XmlReader<B>, XlsxError> {
+    // the encoding declared in the xml prolog is honored, but the prolog itself
+    // must be readable, which is not the case of UTF-16 (with or without BOM)
+    match f.fill_buf()?.first() {
+        Some(0xFF) | Some(0xFE) | Some(0) => {
+            return Err(XlsxError::UnsupportedEncoding(path.to_string()));
+        }
+        _ => (),
+    }
+    let mut r = XmlReader::from_reader(f);
+    r.check_end_names(false)
+        .trim_text(false)
+        .check_comments(false)
+        .expand_empty_elements(true);
+    Ok(r)
+}
+
+/// read the `<si>` elements of a shared strings table
+///
+/// `read_si` is called with the reader positioned right after each `<si>` start tag, along
+/// with its qualified name, and must consume the whole element.
+fn read_sst<B, T, F>(xml: &mut XmlReader<B>, mut read_si: F) -> Result<Vec<T>, XlsxError>
+where
+    B: BufRead,
+    F: FnMut(&mut XmlReader<B>, &[u8]) -> Result<T, XlsxError>,
+{
+    let mut buf = Vec::new();
+    let mut items = Vec::new();
+    let mut unique_count = None;
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"sst" => {
+                let count = get_attribute(e.attributes(), b"uniqueCount")?
+                    .and_then(|count| xml.decode(count).parse::<usize>().ok());
+                if let Some(count) = count {
+                    // the declared count cannot be trusted, don't reserve absurd sizes
+                    items.reserve(min(count, 1_000_000));
+                    unique_count = Some(count);
+                }
+            }
+            Ok(Event::Start(ref e)) if e.local_name() == b"si" => {
+                items.push(read_si(xml, e.name())?);
+            }
+            Ok(Event::End(ref e)) if e.local_name() == b"sst" => break,
+            Ok(Event::Eof) => return Err(XlsxError::XmlEof("sst")),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+    if let Some(count) = unique_count {
+        if count != items.len() {
+            warn!(
+                "shared strings table declares {} unique strings but has {}",
+                count,
+                items.len()
+            );
+        }
+    }
+    Ok(items)
+}
+
+/// index the `<si>` elements of the raw shared strings table at `path`
+///
+/// Falls back to decoding all strings if the table is not encoded in UTF-8, as strings are
+/// then decoded without the xml prolog declaring the encoding.
+fn index_shared_strings(bytes: Vec<u8>, path: &str) -> Result<SharedStrings, XlsxError> {
+    let (offsets, encoding) = {
+        let mut xml = xml_reader_from(&bytes[..], path)?;
+        let mut buf = Vec::new();
+        let offsets = read_sst(&mut xml, |xml, si| {
+            // the reader is right after the `<si>` start tag, which cannot contain any `<`
+            let end = xml.buffer_position();
+            let start = bytes[..end].iter().rposition(|&b| b == b'<').unwrap_or(end);
+            buf.clear();
+            xml.read_to_end(si, &mut buf)?;
+            Ok(start)
+        })?;
+        (offsets, xml.encoding())
+    };
+    if encoding != encoding_rs::UTF_8 {
+        let mut xml = xml_reader_from(&bytes[..], path)?;
+        let strings = read_sst(&mut xml, |xml, si| {
+            Ok(read_string(xml, si)?.unwrap_or_default())
+        })?;
+        return Ok(SharedStrings::Decoded(strings));
+    }
+    Ok(SharedStrings::Indexed(Box::new(IndexedStrings {
+        xml: bytes,
+        offsets,
+        cache: RefCell::new(HashMap::new()),
+    })))
+}
+
+/// decode the shared string at the beginning of `xml`, as indexed by `index_shared_strings`
+fn decode_shared_string(xml: &[u8]) -> Result<String, XlsxError> {
+    let mut xml = xml_reader_from(xml, "")?;
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) if e.local_name() == b"si" => {
+                return Ok(read_string(&mut xml, e.name())?.unwrap_or_default());
+            }
+            Ok(Event::Eof) => return Err(XlsxError::XmlEof("si")),
+            Err(e) => return Err(XlsxError::Xml(e)),
+            _ => (),
+        }
+    }
+}
+
 /// search through an Element's attributes for the named one
 fn get_attribute<'a>(atts: Attributes<'a>, n: &[u8]) -> Result<Option<&'a [u8]>, XlsxError> {
     for a in atts {
@@ -780,15 +3097,26 @@ fn get_attribute<'a>(atts: Attributes<'a>, n: &[u8]) -> Result<Option<&'a [u8]>,
     Ok(None)
 }
 
-fn read_sheet<T, F>(
+fn read_sheet<F>(xml: &mut XlsReader<'_>, push_cell: &mut F) -> Result<(), XlsxError>
+where
+    F: FnMut(
+        &mut XlsReader<'_>,
+        &BytesStart<'_>,
+        (u32, u32),
+        &BytesStart<'_>,
+    ) -> Result<(), XlsxError>,
+{
+    read_sheet_rows(xml, std::usize::MAX, push_cell)
+}
+
+/// read at most `max_rows` rows of a sheetData node, the following rows are left unread
+fn read_sheet_rows<F>(
     xml: &mut XlsReader<'_>,
-    cells: &mut Vec<Cell<T>>,
+    max_rows: usize,
     push_cell: &mut F,
 ) -> Result<(), XlsxError>
 where
-    T: Clone + Default + PartialEq,
     F: FnMut(
-        &mut Vec<Cell<T>>,
         &mut XlsReader<'_>,
         &BytesStart<'_>,
         (u32, u32),
@@ -797,17 +3125,42 @@ where
 {
     let mut buf = Vec::new();
     let mut cell_buf = Vec::new();
+    // `r` attributes are optional on both rows and cells, missing positions
+    // are implied by the position of the previous row/cell
+    let mut row: Option<u32> = None;
+    let mut next_col = 0;
+    let mut rows = 0;
     loop {
         buf.clear();
         match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref row_element)) if row_element.local_name() == b"row" => {
+                if rows == max_rows {
+                    return Ok(());
+                }
+                rows += 1;
+                row = Some(match get_attribute(row_element.attributes(), b"r")? {
+                    Some(r) => {
+                        let r: u32 = std::str::from_utf8(r).unwrap_or("").parse()?;
+                        if r == 0 || r > MAX_ROWS {
+                            return Err(XlsxError::RangeOutOfBounds(r.to_string()));
+                        }
+                        r - 1
+                    }
+                    None => row.map_or(0, |r| r + 1),
+                });
+                next_col = 0;
+            }
             Ok(Event::Start(ref c_element)) if c_element.local_name() == b"c" => {
-                let pos = get_attribute(c_element.attributes(), b"r")
-                    .and_then(|o| o.ok_or(XlsxError::CellRAttribute))
-                    .and_then(get_row_column)?;
+                let pos = match get_attribute(c_element.attributes(), b"r")? {
+                    Some(r) => get_row_column(r)?,
+                    None => (row.unwrap_or(0), next_col),
+                };
+                row = Some(pos.0);
+                next_col = pos.1 + 1;
                 loop {
                     cell_buf.clear();
                     match xml.read_event(&mut cell_buf) {
-                        Ok(Event::Start(ref e)) => push_cell(cells, xml, e, pos, c_element)?,
+                        Ok(Event::Start(ref e)) => push_cell(xml, e, pos, c_element)?,
                         Ok(Event::End(ref e)) if e.local_name() == b"c" => break,
                         Ok(Event::Eof) => return Err(XlsxError::XmlEof("c")),
                         Err(e) => return Err(XlsxError::Xml(e)),
@@ -823,50 +3176,118 @@ where
     }
 }
 
-/// read sheetData node
-fn read_sheet_data(
+/// read sheetData node, calling `push_cell` for each non empty cell
+fn read_sheet_data<'s, F>(
     xml: &mut XlsReader<'_>,
-    strings: &[String],
-    formats: &[CellFormat],
-    cells: &mut Vec<Cell<DataType>>,
-) -> Result<(), XlsxError> {
+    context: SheetContext<'s>,
+    push_cell: &mut F,
+) -> Result<(), XlsxError>
+where
+    F: FnMut((u32, u32), DataTypeRef<'s>),
+{
+    let lenient = context.options.lenient;
+    read_sheet_data_with(xml, context, std::usize::MAX, push_cell, &mut |_, e| {
+        if lenient {
+            Ok(())
+        } else {
+            Err(e)
+        }
+    })
+}
+
+/// read (at most `max_rows` rows of) sheetData node, calling `push_cell` for each non
+/// empty cell
+///
+/// Cells whose value cannot be parsed are passed to `on_error`, which decides whether
+/// the whole sheet parsing must fail or not
+fn read_sheet_data_with<'s, F, E>(
+    xml: &mut XlsReader<'_>,
+    context: SheetContext<'s>,
+    max_rows: usize,
+    push_cell: &mut F,
+    on_error: &mut E,
+) -> Result<(), XlsxError>
+where
+    F: FnMut((u32, u32), DataTypeRef<'s>),
+    E: FnMut((u32, u32), XlsxError) -> Result<(), XlsxError>,
+{
+    let SheetContext {
+        strings,
+        formats,
+        options,
+        date_system,
+        ..
+    } = context;
+    // formats are only used to detect dates
+    let formats = if options.convert_dates { formats } else { &[] };
+
     /// read the contents of a <v> cell
-    fn read_value<'a>(
+    fn read_value<'s>(
         v: String,
-        strings: &[String],
+        strings: &'s SharedStrings,
         formats: &[CellFormat],
-        c_element: &BytesStart<'a>,
-    ) -> Result<DataType, XlsxError> {
-        let is_date_time = match get_attribute(c_element.attributes(), b"s") {
-            Ok(Some(style)) => {
+        date_system: DateSystem,
+        c_element: &BytesStart<'_>,
+    ) -> Result<DataTypeRef<'s>, XlsxError> {
+        // read both `s` and `t` in a single pass over the attributes, this is on the hot path
+        // of every cell
+        let (mut style, mut typ) = (None, None);
+        for a in c_element.attributes().with_checks(false) {
+            match a {
+                Ok(Attribute {
+                    key: b"s",
+                    value: Cow::Borrowed(v),
+                }) => style = Some(v),
+                Ok(Attribute {
+                    key: b"t",
+                    value: Cow::Borrowed(v),
+                }) => typ = Some(v),
+                Ok(_) => (),
+                Err(e) => return Err(XlsxError::Xml(e)),
+            }
+        }
+        let format = match style {
+            Some(style) => {
                 let id: usize = std::str::from_utf8(style).unwrap_or("0").parse()?;
-                match formats.get(id) {
-                    Some(CellFormat::Date) => true,
-                    _ => false,
-                }
+                formats.get(id)
             }
-            _ => false,
+            None => None,
         };
 
-        match get_attribute(c_element.attributes(), b"t")? {
+        match typ {
             Some(b"s") => {
                 // shared string
                 let idx: usize = v.parse()?;
-                Ok(DataType::String(strings[idx].clone()))
+                match strings.get(idx)? {
+                    Some(Cow::Borrowed(s)) => Ok(DataTypeRef::SharedString(s)),
+                    Some(Cow::Owned(s)) => Ok(DataTypeRef::String(s)),
+                    None => Err(XlsxError::Unexpected("shared string index out of bounds")),
+                }
             }
             Some(b"b") => {
-                // boolean
-                Ok(DataType::Bool(v != "0"))
+                // boolean, normally stored as 1 or 0 but some producers write
+                // the result of boolean formulas as (case insensitive) true or false
+                match &*v {
+                    "1" => Ok(DataTypeRef::Bool(true)),
+                    "0" => Ok(DataTypeRef::Bool(false)),
+                    v => Ok(DataTypeRef::Bool(v.to_ascii_lowercase().parse()?)),
+                }
             }
             Some(b"e") => {
-                // error
-                Ok(DataType::Error(v.parse()?))
+                // error, newer error values (e.g. #SPILL!) which have no
+                // `CellErrorType` counterpart are returned as string
+                match v.parse() {
+                    Ok(e) => Ok(DataTypeRef::Error(e)),
+                    Err(_) => Ok(DataTypeRef::String(v)),
+                }
             }
             Some(b"d") => {
-                // date
-                // TODO: create a DataType::Date
-                // currently just return as string (ISO 8601)
-                Ok(DataType::String(v))
+                // date, stored as an ISO 8601 string
+                // returned as string if it cannot be parsed
+                match parse_iso_date_time(&v, date_system) {
+                    Some(n) => Ok(DataTypeRef::DateTime(n)),
+                    None => Ok(DataTypeRef::String(v)),
+                }
             }
             Some(b"str") => {
                 // see http://officeopenxml.com/SScontentOverview.php
@@ -876,39 +3297,27 @@ fn read_sheet_data(
                 // TODO: Fully support a DataType::Formula representing both Formula string &
                 // last calculated value?
                 //
-                // NB: the result of a formula may not be a numeric value (=A3&" "&A4).
-                // We do try an initial parse as Float for utility, but fall back to a string
-                // representation if that fails
-                v.parse().map(DataType::Float).or(Ok(DataType::String(v)))
+                // NB: numeric formula results are stored without 't' attribute, this type is
+                // only used for text results, even if they look like numbers (=TEXT(A1, "0"))
+                Ok(DataTypeRef::String(v))
             }
             Some(b"n") => {
                 // n - number
                 if v.is_empty() {
-                    Ok(DataType::Empty)
+                    Ok(DataTypeRef::Empty)
                 } else {
                     v.parse()
-                        .map(|n| {
-                            if is_date_time {
-                                DataType::DateTime(n)
-                            } else {
-                                DataType::Float(n)
-                            }
-                        })
+                        .map(|n| read_number(n, format))
                         .map_err(XlsxError::ParseFloat)
                 }
             }
             None => {
                 // If type is not known, we try to parse as Float for utility, but fall back to
                 // String if this fails.
-                v.parse()
-                    .map(|n| {
-                        if is_date_time {
-                            DataType::DateTime(n)
-                        } else {
-                            DataType::Float(n)
-                        }
-                    })
-                    .or(Ok(DataType::String(v)))
+                match parse_untyped_number(&v) {
+                    Some(n) => Ok(read_number(n, format)),
+                    None => Ok(DataTypeRef::String(v)),
+                }
             }
             Some(b"is") => {
                 // this case should be handled in outer loop over cell elements, in which
@@ -924,35 +3333,180 @@ fn read_sheet_data(
         }
     }
 
-    read_sheet(xml, cells, &mut |cells, xml, e, pos, c_element| {
+    /// xlsx stores every number as a double, there is no dedicated integer representation
+    /// so numbers are always read as `Float` (or `DateTime`, `Duration`), never as `Int`
+    fn read_number<'s>(n: f64, format: Option<&CellFormat>) -> DataTypeRef<'s> {
+        // `f64::from_str` accepts "inf", "NaN", "infinity" etc. and overflows to infinity,
+        // none of which Excel can store: report them as #NUM! instead
+        if !n.is_finite() {
+            return DataTypeRef::Error(CellErrorType::Num);
+        }
+        match format {
+            Some(CellFormat::Date) => DataTypeRef::DateTime(n),
+            Some(CellFormat::TimeDelta) => DataTypeRef::Duration(n),
+            _ => DataTypeRef::Float(n),
+        }
+    }
+
+    let mut buf = Vec::new();
+    read_sheet_rows(xml, max_rows, &mut |xml, e, pos, c_element| {
+        buf.clear();
         match e.local_name() {
             b"is" => {
                 // inlineStr
                 if let Some(s) = read_string(xml, e.name())? {
-                    cells.push(Cell::new(pos, DataType::String(s)));
+                    push_cell(pos, DataTypeRef::String(s));
                 }
             }
             b"v" => {
                 // value
-                let v = xml.read_text(e.name(), &mut Vec::new())?;
-                match read_value(v, strings, formats, c_element)? {
-                    DataType::Empty => (),
-                    v => cells.push(Cell::new(pos, v)),
+                let v = xml.read_text(e.name(), &mut buf)?;
+                match read_value(v, strings, formats, date_system, c_element) {
+                    Ok(DataTypeRef::Empty) => (),
+                    Ok(v) => push_cell(pos, v),
+                    Err(e) => on_error(pos, e)?,
                 }
             }
-            b"f" => xml.read_to_end(e.name(), &mut Vec::new())?,
+            b"f" => xml.read_to_end(e.name(), &mut buf)?,
             _n => return Err(XlsxError::UnexpectedNode("v, f, or is")),
         }
         Ok(())
     })
 }
 
+/// Parse the value of a cell without `t` attribute as a number
+///
+/// Only plain decimal numbers (with optional sign, fraction and exponent) are accepted: texts
+/// such as `inf` or `NaN`, which `f64::from_str` would happily parse, are kept as strings.
+fn parse_untyped_number(v: &str) -> Option<f64> {
+    let is_numeric = v.bytes().all(|b| {
+        b.is_ascii_digit() || b == b'.' || b == b'-' || b == b'+' || b == b'e' || b == b'E'
+    });
+    if is_numeric && v.bytes().any(|b| b.is_ascii_digit()) {
+        v.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Converts an ISO 8601 date (`2016-05-25`), date time (`2016-05-25T10:30:00.5`) or
+/// time (`10:30:00`) into a serial date of the `date_system`, as stored in
+/// `DataType::DateTime`
+///
+/// Time zone designators are not supported and a trailing `Z` is ignored. Like Excel, the
+/// 1900 date system counts a (non existing) 1900-02-29: dates before 1900-03-01 are one
+/// serial lower than later ones would suggest, so they match the serials Excel stores.
+fn parse_iso_date_time(v: &str, date_system: DateSystem) -> Option<f64> {
+    let parse_date = |date: &str| -> Option<f64> {
+        let mut parts = date.splitn(3, '-');
+        let y: i64 = parts.next()?.parse().ok()?;
+        let m: i64 = parts.next()?.parse().ok()?;
+        let d: i64 = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+            return None;
+        }
+        // days from civil (http://howardhinnant.github.io/date_algorithms.html)
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = if y >= 0 { y } else { y - 399 } / 400;
+        let yoe = y - era * 400;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let unix_days = era * 146_097 + doe - 719_468;
+        let serial = unix_days + 25569;
+        let serial = match date_system {
+            DateSystem::V1900 if serial < 61 => serial - 1,
+            DateSystem::V1900 => serial,
+            DateSystem::V1904 => serial - 1462,
+        };
+        Some(serial as f64)
+    };
+    fn parse_time(time: &str) -> Option<f64> {
+        let time = time.trim_end_matches('Z');
+        let mut parts = time.splitn(3, ':');
+        let h: u32 = parts.next()?.parse().ok()?;
+        let m: u32 = parts.next()?.parse().ok()?;
+        let s: f64 = match parts.next() {
+            Some(s) if !s.starts_with(|c: char| c.is_ascii_digit()) => return None,
+            Some(s) => s.parse().ok()?,
+            None => 0.,
+        };
+        if h > 24 || m > 59 || s >= 60. {
+            return None;
+        }
+        Some((f64::from(h * 3600 + m * 60) + s) / 86400.)
+    }
+    match v.find('T') {
+        Some(i) => Some(parse_date(&v[..i])? + parse_time(&v[i + 1..])?),
+        None if v.contains(':') => parse_time(v),
+        None => parse_date(v),
+    }
+}
+
 // This tries to detect number formats that are definitely date/time formats.
 // This is definitely not perfect!
 fn is_custom_date_format(format: &str) -> bool {
     format.bytes().all(|c| b"mdyMDYhsHS-/. \\".contains(&c))
 }
 
+// Elapsed time formats have at least one bracketed hour, minute or second unit
+// (e.g. `[h]:mm:ss`, `[mm]:ss`), and otherwise only hold time components.
+fn is_custom_duration_format(format: &str) -> bool {
+    let mut elapsed = false;
+    let mut rest = format;
+    while let Some(open) = rest.find('[') {
+        if !rest[..open].bytes().all(|c| b"hmsHMS:.0 \\".contains(&c)) {
+            return false;
+        }
+        let close = match rest[open..].find(']') {
+            Some(close) => open + close,
+            None => return false,
+        };
+        let unit = &rest[open + 1..close];
+        // other bracketed sections are colors or locales (e.g. `[Red]`, `[$-409]`)
+        if !unit.is_empty() && unit.bytes().all(|c| b"hmsHMS".contains(&c)) {
+            elapsed = true;
+        }
+        rest = &rest[close + 1..];
+    }
+    elapsed && rest.bytes().all(|c| b"hmsHMS:.0 \\".contains(&c))
+}
+
+/// built-in number format codes (ECMA-376 18.8.30), locale dependent ones excepted
+fn builtin_format_code(id: &[u8]) -> Option<&'static str> {
+    let code = match id {
+        b"0" => "General",
+        b"1" => "0",
+        b"2" => "0.00",
+        b"3" => "#,##0",
+        b"4" => "#,##0.00",
+        b"9" => "0%",
+        b"10" => "0.00%",
+        b"11" => "0.00E+00",
+        b"12" => "# ?/?",
+        b"13" => "# ??/??",
+        b"14" => "mm-dd-yy",
+        b"15" => "d-mmm-yy",
+        b"16" => "d-mmm",
+        b"17" => "mmm-yy",
+        b"18" => "h:mm AM/PM",
+        b"19" => "h:mm:ss AM/PM",
+        b"20" => "h:mm",
+        b"21" => "h:mm:ss",
+        b"22" => "m/d/yy h:mm",
+        b"37" => "#,##0 ;(#,##0)",
+        b"38" => "#,##0 ;[Red](#,##0)",
+        b"39" => "#,##0.00;(#,##0.00)",
+        b"40" => "#,##0.00;[Red](#,##0.00)",
+        b"45" => "mm:ss",
+        b"46" => "[h]:mm:ss",
+        b"47" => "mmss.0",
+        b"48" => "##0.0E+0",
+        b"49" => "@",
+        _ => return None,
+    };
+    Some(code)
+}
+
 fn is_builtin_date_format_id(id: &[u8]) -> bool {
     match id {
     // mm-dd-yy
@@ -983,22 +3537,15 @@ fn is_builtin_date_format_id(id: &[u8]) -> bool {
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct Dimensions {
-    start: (u32, u32),
-    end: (u32, u32),
-}
-
-impl Dimensions {
-    fn len(&self) -> u64 {
-        (self.end.0 - self.start.0 + 1) as u64 * (self.end.1 - self.start.1 + 1) as u64
-    }
-}
-
 /// converts a text representation (e.g. "A6:G67") of a dimension into integers
 /// - top left (row, column),
 /// - bottom right (row, column)
+///
+/// Whole columns ("A:G") span all rows and whole rows ("1:5") span all columns
 fn get_dimension(dimension: &[u8]) -> Result<Dimensions, XlsxError> {
+    if let Some(dim) = get_whole_dimension(dimension) {
+        return dim;
+    }
     let parts: Vec<_> = dimension
         .split(|c| *c == b':')
         .map(|s| get_row_column(s))
@@ -1010,69 +3557,228 @@ fn get_dimension(dimension: &[u8]) -> Result<Dimensions, XlsxError> {
             start: parts[0],
             end: parts[0],
         }),
-        2 => {
-            let rows = parts[1].0 - parts[0].0;
-            let columns = parts[1].1 - parts[0].1;
-            if rows > MAX_ROWS {
-                warn!(
-                    "xlsx has more than maximum number of rows ({} > {})",
-                    rows, MAX_ROWS
-                );
-            }
-            if columns > MAX_COLUMNS {
-                warn!(
-                    "xlsx has more than maximum number of columns ({} > {})",
-                    columns, MAX_COLUMNS
-                );
-            }
-            Ok(Dimensions {
-                start: parts[0],
-                end: parts[1],
-            })
-        }
+        2 => Ok(Dimensions {
+            start: parts[0],
+            end: parts[1],
+        }),
         len => Err(XlsxError::DimensionCount(len)),
     }
 }
 
+/// converts whole columns ("A:G") or whole rows ("1:5") references into dimensions
+///
+/// Returns `None` if the reference is neither
+fn get_whole_dimension(dimension: &[u8]) -> Option<Result<Dimensions, XlsxError>> {
+    let mut parts = dimension.splitn(2, |c| *c == b':');
+    let (start, end) = (parts.next()?, parts.next()?);
+    let all = |s: &[u8], f: fn(&u8) -> bool| !s.is_empty() && s.iter().all(f);
+    // reuse cell references parsing by completing the missing component
+    let cell = |s: &[u8], pre: &[u8], post: &[u8]| {
+        get_row_column(&[pre, s, post].concat()).map_err(|_| {
+            XlsxError::RangeOutOfBounds(String::from_utf8_lossy(dimension).into_owned())
+        })
+    };
+    if all(start, u8::is_ascii_alphabetic) && all(end, u8::is_ascii_alphabetic) {
+        Some(cell(start, b"", b"1").and_then(|(_, start)| {
+            let (_, end) = cell(end, b"", b"1")?;
+            Ok(Dimensions::new((0, start), (MAX_ROWS - 1, end)))
+        }))
+    } else if all(start, u8::is_ascii_digit) && all(end, u8::is_ascii_digit) {
+        Some(cell(start, b"A", b"").and_then(|(start, _)| {
+            let (end, _) = cell(end, b"A", b"")?;
+            Ok(Dimensions::new((start, 0), (end, MAX_COLUMNS - 1)))
+        }))
+    } else {
+        None
+    }
+}
+
 /// converts a text range name into its position (row, column) (0 based index)
+///
+/// Rows must be within `1..=MAX_ROWS` and columns within `A..=XFD`
 fn get_row_column(range: &[u8]) -> Result<(u32, u32), XlsxError> {
-    let (mut row, mut col) = (0, 0);
-    let mut pow = 1;
+    let out_of_bounds = || XlsxError::RangeOutOfBounds(String::from_utf8_lossy(range).into_owned());
+    let (mut row, mut col) = (0u32, 0u32);
+    let mut pow = 1u32;
     let mut readrow = true;
     for c in range.iter().rev() {
         match *c {
             c @ b'0'..=b'9' => {
                 if readrow {
-                    row += ((c - b'0') as u32) * pow;
-                    pow *= 10;
+                    // leading zeros may saturate `pow` but never add to `row`
+                    row = row.saturating_add(((c - b'0') as u32).saturating_mul(pow));
+                    pow = pow.saturating_mul(10);
+                    if row > MAX_ROWS {
+                        return Err(out_of_bounds());
+                    }
                 } else {
                     return Err(XlsxError::NumericColumn(c));
                 }
             }
-            c @ b'A'..=b'Z' => {
+            c @ b'A'..=b'Z' | c @ b'a'..=b'z' => {
                 if readrow {
                     pow = 1;
                     readrow = false;
                 }
-                col += ((c - b'A') as u32 + 1) * pow;
-                pow *= 26;
-            }
-            c @ b'a'..=b'z' => {
-                if readrow {
-                    pow = 1;
-                    readrow = false;
+                let digit = (c.to_ascii_uppercase() - b'A') as u32 + 1;
+                col = col.saturating_add(digit.saturating_mul(pow));
+                pow = pow.saturating_mul(26);
+                if col > MAX_COLUMNS {
+                    return Err(out_of_bounds());
                 }
-                col += ((c - b'a') as u32 + 1) * pow;
-                pow *= 26;
             }
             _ => return Err(XlsxError::Alphanumeric(*c)),
         }
     }
+    if row == 0 {
+        return Err(XlsxError::RangeWithoutRowComponent(
+            String::from_utf8_lossy(range).into_owned(),
+        ));
+    }
+    if col == 0 {
+        return Err(XlsxError::RangeWithoutColumnComponent(
+            String::from_utf8_lossy(range).into_owned(),
+        ));
+    }
     Ok((row - 1, col - 1))
 }
 
+/// reads a <f> node, expanding shared formulas
+///
+/// Cells depending on a shared formula (`<f t="shared" si="0"/>`) have no formula text,
+/// the formula of the master cell is translated to their position instead.
+fn read_formula(
+    xml: &mut XlsReader<'_>,
+    e: &BytesStart<'_>,
+    pos: (u32, u32),
+    shared: &mut SharedFormulas,
+) -> Result<String, XlsxError> {
+    let mut is_shared = false;
+    let mut si = None;
+    for a in e.attributes() {
+        match a? {
+            Attribute {
+                key: b"t",
+                value: v,
+            } => is_shared = &*v == b"shared",
+            Attribute {
+                key: b"si",
+                value: v,
+            } => si = Some(v.into_owned()),
+            _ => (),
+        }
+    }
+    let f = xml.read_text(e.name(), &mut Vec::new())?;
+    match si {
+        Some(si) if is_shared => {
+            if !f.is_empty() {
+                shared.insert(si, (pos, f.clone()));
+                Ok(f)
+            } else if let Some((master, formula)) = shared.get(&si) {
+                let rows = pos.0 as i64 - master.0 as i64;
+                let cols = pos.1 as i64 - master.1 as i64;
+                Ok(shift_formula(formula, rows, cols))
+            } else {
+                Ok(f)
+            }
+        }
+        _ => Ok(f),
+    }
+}
+
+/// translates the relative cell references (e.g. `A1`, but not `$A$1`) of a formula
+///
+/// References shifted out of the sheet are replaced by `#REF!`, like Excel does.
+/// Whole column or row references (e.g. `A:A`) are left untouched.
+fn shift_formula(formula: &str, rows: i64, cols: i64) -> String {
+    let bytes = formula.as_bytes();
+    let mut res = String::with_capacity(formula.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            // string literals, quoted sheet names and structured references are kept as is
+            quote @ b'"' | quote @ b'\'' | quote @ b'[' => {
+                let close = if quote == b'[' { b']' } else { quote };
+                let end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| *b == close)
+                    .map_or(bytes.len(), |p| i + p + 2);
+                res.push_str(&formula[i..end]);
+                i = end;
+            }
+            b if b.is_ascii_alphanumeric() || b == b'$' || b == b'_' || b == b'.' => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|b| {
+                        !(b.is_ascii_alphanumeric() || *b == b'$' || *b == b'_' || *b == b'.')
+                    })
+                    .map_or(bytes.len(), |p| i + p);
+                let token = &formula[i..end];
+                match bytes.get(end) {
+                    // functions and sheet names
+                    Some(b'(') | Some(b'!') => res.push_str(token),
+                    _ => match shift_reference(token, rows, cols) {
+                        Some(r) => res.push_str(&r),
+                        None => res.push_str(token),
+                    },
+                }
+                i = end;
+            }
+            _ => {
+                let c = formula[i..].chars().next().unwrap();
+                res.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    res
+}
+
+/// shifts a single `A1` cell reference, returns `None` if `token` is not a cell reference
+fn shift_reference(token: &str, rows: i64, cols: i64) -> Option<String> {
+    let t = token.as_bytes();
+    let abs_col = t.first() == Some(&b'$');
+    let t = if abs_col { &t[1..] } else { t };
+    let letters = t.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+    if letters == 0 || letters > 3 {
+        return None;
+    }
+    let (col, t) = t.split_at(letters);
+    let abs_row = t.first() == Some(&b'$');
+    let row = if abs_row { &t[1..] } else { t };
+    if row.is_empty() || row.len() > 7 || !row.iter().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let col = col.iter().fold(0i64, |c, b| {
+        c * 26 + (b.to_ascii_uppercase() - b'A') as i64 + 1
+    }) - 1;
+    let row = std::str::from_utf8(row).ok()?.parse::<i64>().ok()? - 1;
+    if col >= MAX_COLUMNS as i64 || row < 0 || row >= MAX_ROWS as i64 {
+        return None;
+    }
+
+    let col = if abs_col { col } else { col + cols };
+    let row = if abs_row { row } else { row + rows };
+    if col < 0 || col >= MAX_COLUMNS as i64 || row < 0 || row >= MAX_ROWS as i64 {
+        return Some("#REF!".to_string());
+    }
+    let mut r = String::with_capacity(token.len());
+    if abs_col {
+        r.push('$');
+    }
+    push_column(col as u32, &mut r);
+    if abs_row {
+        r.push('$');
+    }
+    r.push_str(&(row + 1).to_string());
+    Some(r)
+}
+
 /// attempts to read either a simple or richtext string
-fn read_string(xml: &mut XlsReader<'_>, closing: &[u8]) -> Result<Option<String>, XlsxError> {
+fn read_string<B: BufRead>(
+    xml: &mut XmlReader<B>,
+    closing: &[u8],
+) -> Result<Option<String>, XlsxError> {
     let mut buf = Vec::new();
     let mut val_buf = Vec::new();
     let mut rich_buffer: Option<String> = None;
@@ -1089,7 +3795,8 @@ fn read_string(xml: &mut XlsReader<'_>, closing: &[u8]) -> Result<Option<String>
             Ok(Event::Start(ref e)) if e.local_name() == b"rPh" => {
                 is_phonetic_text = true;
             }
-            Ok(Event::End(ref e)) if e.local_name() == closing => {
+            // `closing` is the qualified name of the opening element, as is `e.name()`
+            Ok(Event::End(ref e)) if e.name() == closing => {
                 return Ok(rich_buffer);
             }
             Ok(Event::End(ref e)) if e.local_name() == b"rPh" => {
@@ -1131,6 +3838,88 @@ fn test_dimensions() {
             end: (1_048_575, 16_383),
         }
     );
+    assert_eq!(
+        get_dimension(b"B:D").unwrap(),
+        Dimensions::new((0, 1), (1_048_575, 3))
+    );
+    assert_eq!(
+        get_dimension(b"c:c").unwrap(),
+        Dimensions::new((0, 2), (1_048_575, 2))
+    );
+    assert_eq!(
+        get_dimension(b"2:5").unwrap(),
+        Dimensions::new((1, 0), (4, 16_383))
+    );
+}
+
+#[test]
+fn test_invalid_cell_reference() {
+    match get_row_column(b"A") {
+        Err(XlsxError::RangeWithoutRowComponent(r)) => assert_eq!(r, "A"),
+        e => panic!("unexpected {:?}", e),
+    }
+    match get_row_column(b"B0") {
+        Err(XlsxError::RangeWithoutRowComponent(r)) => assert_eq!(r, "B0"),
+        e => panic!("unexpected {:?}", e),
+    }
+    match get_row_column(b"5") {
+        Err(XlsxError::RangeWithoutColumnComponent(r)) => assert_eq!(r, "5"),
+        e => panic!("unexpected {:?}", e),
+    }
+    assert!(get_dimension(b"A1:C").is_err());
+    assert!(get_dimension(b"A:3").is_err());
+    for r in &[&b"A:XFE"[..], b"0:3", b"1:1048577"] {
+        match get_dimension(r) {
+            Err(XlsxError::RangeOutOfBounds(s)) => assert_eq!(s.as_bytes(), *r),
+            e => panic!("unexpected {:?} for {:?}", e, r),
+        }
+    }
+    assert_eq!(get_row_column(b"XFD1048576").unwrap(), (1_048_575, 16_383));
+    assert_eq!(get_row_column(b"xfd00001").unwrap(), (0, 16_383));
+    for r in &[
+        &b"XFE1"[..],
+        b"AAAA1",
+        b"A1048577",
+        b"ZZZZZZZZZZZZZZ1",
+        b"A99999999999999999999",
+    ] {
+        match get_row_column(r) {
+            Err(XlsxError::RangeOutOfBounds(_)) => (),
+            e => panic!("unexpected {:?} for {:?}", e, r),
+        }
+    }
+    assert_eq!(
+        get_row_column(b"").unwrap_err().to_string(),
+        "Invalid cell reference '': missing row component"
+    );
+}
+
+#[test]
+fn test_shift_formula() {
+    assert_eq!(shift_formula("A2*2", 1, 0), "A3*2");
+    assert_eq!(shift_formula("SUM(A1:B2)+$C$1", 2, 1), "SUM(B3:C4)+$C$1");
+    assert_eq!(shift_formula("$A1+A$1", 1, 1), "$A2+B$1");
+    assert_eq!(shift_formula("Sheet2!A1&\"A1\"", 0, 2), "Sheet2!C1&\"A1\"");
+    assert_eq!(shift_formula("'My Sheet'!Z9", 0, 1), "'My Sheet'!AA9");
+    assert_eq!(
+        shift_formula("LOG10(A1)+OneRange", 1, 0),
+        "LOG10(A2)+OneRange"
+    );
+    assert_eq!(shift_formula("A1-1.5", -1, 0), "#REF!-1.5");
+    assert_eq!(shift_formula("Table1[Col1]", 1, 1), "Table1[Col1]");
+}
+
+#[test]
+fn test_is_custom_duration_format() {
+    assert!(is_custom_duration_format("[h]:mm:ss"));
+    assert!(is_custom_duration_format("[hh]:mm"));
+    assert!(is_custom_duration_format("[mm]:ss.00"));
+    assert!(is_custom_duration_format("[Red][h]:mm"));
+    assert!(!is_custom_duration_format("hh:mm:ss"));
+    assert!(!is_custom_duration_format("[Red]0.00"));
+    assert!(!is_custom_duration_format("[$-409]d/m/yyyy"));
+    assert!(!is_custom_duration_format("[h]:mm \"hours\""));
+    assert!(!is_custom_duration_format("[h"));
 }
 
 #[test]
@@ -1170,3 +3959,52 @@ fn test_parse_error() {
         CellErrorType::Value
     );
 }
+
+#[test]
+fn test_parse_untyped_number() {
+    assert_eq!(parse_untyped_number("1"), Some(1.));
+    assert_eq!(parse_untyped_number("-1.5"), Some(-1.5));
+    assert_eq!(parse_untyped_number("1.5E3"), Some(1500.));
+    assert_eq!(parse_untyped_number("1e2"), Some(100.));
+    assert_eq!(parse_untyped_number("+2.5e-1"), Some(0.25));
+    assert_eq!(
+        parse_untyped_number("10000000000000000000"),
+        Some(10_000_000_000_000_000_000.)
+    );
+    assert_eq!(parse_untyped_number(""), None);
+    assert_eq!(parse_untyped_number("e"), None);
+    assert_eq!(parse_untyped_number("1,5"), None);
+    assert_eq!(parse_untyped_number(" 1"), None);
+    assert_eq!(parse_untyped_number("inf"), None);
+    assert_eq!(parse_untyped_number("NaN"), None);
+    assert_eq!(parse_untyped_number("1-2"), None);
+}
+
+#[test]
+fn test_parse_iso_date_time() {
+    let parse = |v| parse_iso_date_time(v, DateSystem::V1900);
+    assert_eq!(parse("1970-01-01"), Some(25569.));
+    assert_eq!(parse("2016-05-25"), Some(42515.));
+    assert_eq!(parse("2016-05-25T00:00:00"), Some(42515.));
+    assert_eq!(parse("2016-05-25T12:00:00Z"), Some(42515.5));
+    assert_eq!(parse("2016-05-25T06:00"), Some(42515.25));
+    assert_eq!(parse("1900-03-01"), Some(61.));
+    assert_eq!(parse("18:00:00"), Some(0.75));
+    assert_eq!(parse("00:00:01.5"), Some(1.5 / 86400.));
+    assert_eq!(parse(""), None);
+    assert_eq!(parse("2016-13-01"), None);
+    assert_eq!(parse("2016-05"), None);
+    assert_eq!(parse("2016-05-25T25:00:00"), None);
+    assert_eq!(parse("not a date"), None);
+
+    // Excel counts 1900-02-29, earlier dates are one serial lower
+    assert_eq!(parse("1900-01-01"), Some(1.));
+    assert_eq!(parse("1900-02-28"), Some(59.));
+    assert_eq!(parse("1900-02-28T12:00:00"), Some(59.5));
+
+    let parse = |v| parse_iso_date_time(v, DateSystem::V1904);
+    assert_eq!(parse("1904-01-01"), Some(0.));
+    assert_eq!(parse("2016-05-25"), Some(41053.));
+    assert_eq!(parse("1900-02-28"), Some(-1402.));
+    assert_eq!(parse("18:00:00"), Some(0.75));
+}
@@ -1,6 +1,12 @@
 use calamine::CellErrorType::*;
-use calamine::DataType::{Bool, DateTime, Empty, Error, Float, String};
-use calamine::{open_workbook, open_workbook_auto, Ods, Reader, Xls, Xlsb, Xlsx};
+use calamine::DataType::{Bool, DateTime, Duration, Empty, Error, Float, String};
+use calamine::{
+    open_workbook, open_workbook_auto, CalcMode, CalcProperties, Cell, ConditionalFormat,
+    ConditionalFormatRule, DataType, DataTypeRef, DataValidation, DateSystem, Dimensions, Ods,
+    Pane, PaneState, PivotCacheInfo, Range, Reader, SheetFormat, SheetType, SheetVisible, Xls,
+    Xlsb, Xlsx, XlsxError, XlsxOptions,
+};
+use std::borrow::Cow;
 use std::io::Cursor;
 use std::sync::Once;
 
@@ -391,6 +397,69 @@ fn xlsx_richtext_namespaced() {
     );
 }
 
+#[test]
+fn xlsx_prefixed_elements() {
+    setup();
+
+    // every spreadsheetml element uses a `x:` namespace prefix
+    let path = format!("{}/tests/prefixed.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.sheet_names(), ["Data", "Other"]);
+    assert_eq!(excel.active_sheet(), Some("Other"));
+    assert_eq!(
+        excel.defined_names(),
+        [("Total".to_string(), "Data!$B$3".to_string())]
+    );
+
+    let range = excel.worksheet_range("Data").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [
+                String("name".to_string()),
+                String("rich".to_string()),
+                Empty
+            ],
+            [String("inline".to_string()), DateTime(44197.), Bool(true)],
+            [Float(1.5), Float(3.), Empty]
+        ]
+    );
+    let formulas = excel.worksheet_formula("Data").unwrap().unwrap();
+    assert_eq!(formulas.get_value((2, 1)), Some(&"A3*2".to_string()));
+    let range = excel.worksheet_range("Other").unwrap().unwrap();
+    range_eq!(range, [[Float(2.)]]);
+}
+
+#[test]
+fn xlsx_custom_part_paths() {
+    setup();
+
+    // parts are only found through the package and workbook relationships
+    let path = format!("{}/tests/custom_paths.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.sheet_names(), ["First", "Second"]);
+    let range = excel.worksheet_range("First").unwrap().unwrap();
+    range_eq!(range, [[String("first".to_string()), DateTime(44197.)]]);
+    let range = excel.worksheet_range("Second").unwrap().unwrap();
+    range_eq!(range, [[String("second".to_string())]]);
+}
+
+#[test]
+fn xlsx_template() {
+    setup();
+
+    // no package relationships, the workbook is found through its (template) content type
+    let path = format!("{}/tests/template.xltx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.sheet_names(), ["Template"]);
+    let range = excel.worksheet_range("Template").unwrap().unwrap();
+    range_eq!(range, [[String("title".to_string()), Float(1.)]]);
+
+    let mut excel = open_workbook_auto(&path).unwrap();
+    let range = excel.worksheet_range("Template").unwrap().unwrap();
+    range_eq!(range, [[String("title".to_string()), Float(1.)]]);
+}
+
 #[test]
 fn defined_names_xlsx() {
     setup();
@@ -505,6 +574,9 @@ fn search_references() {
     let references = vba.get_references();
     let names = references.iter().map(|r| &*r.name).collect::<Vec<&str>>();
     assert_eq!(names, vec!["stdole", "Office"]);
+    assert!(references
+        .iter()
+        .all(|r| r.kind == calamine::vba::ReferenceKind::Registered));
 }
 
 #[test]
@@ -523,6 +595,57 @@ fn formula_xlsx() {
     range_eq!(formula, [["B1+OneRange".to_string()]]);
 }
 
+#[test]
+fn worksheet_formulas_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/formulas.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let values = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(values.start(), Some((1, 1)));
+    assert_eq!(values.end(), Some((2, 2)));
+
+    // D4 has no cached value, the formula range is extended to it
+    let formulas = excel.worksheet_formulas("Sheet1").unwrap().unwrap();
+    assert_eq!(formulas.start(), Some((1, 1)));
+    assert_eq!(formulas.end(), Some((3, 3)));
+    range_eq!(
+        formulas,
+        [
+            [Empty, String("B2*2".to_string()), Empty],
+            [Empty, String("B3&\"y\"".to_string()), Empty],
+            [Empty, Empty, String("SUM(B2:C2)".to_string())]
+        ]
+    );
+
+    assert!(excel.worksheet_formulas("missing").is_none());
+}
+
+#[test]
+fn shared_formulas_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/formulas.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let formula = excel.worksheet_formula("Shared").unwrap().unwrap();
+    range_eq!(
+        formula,
+        [
+            ["A2*2+$A$1".to_string()],
+            ["A3*2+$A$1".to_string()],
+            ["A4*2+$A$1".to_string()]
+        ]
+    );
+
+    let formulas = excel.worksheet_formulas("Shared").unwrap().unwrap();
+    assert_eq!(
+        formulas.get_value((3, 1)),
+        Some(&String("A4*2+$A$1".to_string()))
+    );
+}
+
 #[test]
 fn formula_xlsb() {
     setup();
@@ -615,7 +738,14 @@ fn empty_sheet() {
         assert_eq!(range.start(), None, "wrong start");
         assert_eq!(range.end(), None, "wrong end");
         assert_eq!(range.get_size(), (0, 0), "wrong size");
+        assert!(range.is_empty());
+        assert_eq!(range.get_value((0, 0)), None);
+        assert_eq!(range.get((0, 0)), None);
+        assert_eq!(range.rows().count(), 0);
+        assert_eq!(range.used_cells().count(), 0);
     }
+    // a missing sheet is not an empty sheet
+    assert!(excel.worksheet_range("NotASheet").is_none());
 }
 
 #[test]
@@ -767,3 +897,1862 @@ fn issue_221() {
         ]
     );
 }
+
+#[test]
+fn range_columns() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    let (start, end) = (range.start().unwrap(), range.end().unwrap());
+    assert_eq!(range.columns().len(), range.width());
+    for j in 0..range.width() {
+        let column = range.columns().nth(j).unwrap().collect::<Vec<_>>();
+        let expected = (start.0..=end.0)
+            .map(|i| range.get_value((i, start.1 + j as u32)).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(column, expected);
+    }
+    let last = range.columns().next_back().unwrap().collect::<Vec<_>>();
+    assert_eq!(last[2], &String("c".to_string()));
+}
+
+#[test]
+fn merged_regions_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/merged_range.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let merged = excel.worksheet_merge_cells("Sheet1").unwrap().unwrap();
+    assert_eq!(
+        merged,
+        vec![
+            Dimensions::new((0, 0), (0, 2)),
+            Dimensions::new((2, 1), (3, 2))
+        ]
+    );
+    assert!(merged[1].contains(3, 2));
+    assert!(!merged[1].contains(1, 1));
+    assert!(excel.worksheet_merge_cells("NotASheet").is_none());
+}
+
+#[test]
+fn formula_str_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/formula_str.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [[
+            Float(123.),
+            String("123".to_string()),
+            String("123abc".to_string())
+        ]]
+    );
+}
+
+#[test]
+fn defined_names_scoped_xlsx() {
+    setup();
+
+    let path = format!(
+        "{}/tests/defined_names_scope.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    assert_eq!(
+        excel.defined_names_scoped(),
+        vec![
+            ("Rate", "Sheet2!$A$1", Some("Sheet2")),
+            ("Tax", "Sheet1!$B$2", None)
+        ]
+    );
+    assert_eq!(excel.defined_names().len(), 2);
+
+    // invalid scopes are considered global
+    let path = format!(
+        "{}/tests/invalid_local_sheet_id.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(
+        excel.defined_names_scoped(),
+        vec![("Rate", "Sheet2!$A$1", None), ("Tax", "Sheet1!$B$2", None)]
+    );
+}
+
+#[test]
+fn bom_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/bom.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(range, [[String("bom".to_string()), Float(2.)]]);
+}
+
+#[test]
+fn xlsb_opened_as_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsb", env!("CARGO_MANIFEST_DIR"));
+    match open_workbook::<Xlsx<_>, _>(&path) {
+        Err(XlsxError::BinaryWorkbook) => (),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("xlsb file must not be read as xlsx"),
+    }
+}
+
+#[test]
+fn worksheet_cells_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let mut cells = Vec::new();
+    excel
+        .worksheet_cells("issue2", |pos, v| cells.push((pos, v)))
+        .unwrap()
+        .unwrap();
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    let start = range.start().unwrap();
+    let expected = range
+        .used_cells()
+        .map(|(i, j, v)| ((start.0 + i as u32, start.1 + j as u32), v.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(cells, expected);
+    assert!(excel.worksheet_cells("NotASheet", |_, _| ()).is_none());
+}
+
+#[test]
+fn worksheet_range_ref_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let expected = excel.worksheet_range("issue2").unwrap().unwrap();
+    let range = excel.worksheet_range_ref("issue2").unwrap().unwrap();
+    assert_eq!(range.start(), expected.start());
+    assert_eq!(range.end(), expected.end());
+    assert_eq!(
+        range.get_value((1, 1)),
+        Some(&DataTypeRef::SharedString("b"))
+    );
+    let owned = range
+        .rows()
+        .map(|r| r.iter().cloned().map(DataType::from).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let expected = expected.rows().map(|r| r.to_vec()).collect::<Vec<_>>();
+    assert_eq!(owned, expected);
+    assert!(excel.worksheet_range_ref("NotASheet").is_none());
+}
+
+#[test]
+fn sheets_metadata_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/sheet_visibility.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let sheets = excel.sheets_metadata();
+    assert_eq!(
+        sheets
+            .iter()
+            .map(|s| (&*s.name, s.visible))
+            .collect::<Vec<_>>(),
+        [
+            ("Visible", SheetVisible::Visible),
+            ("Hidden", SheetVisible::Hidden),
+            ("VeryHidden", SheetVisible::VeryHidden)
+        ]
+    );
+    assert_eq!(excel.sheet_names(), ["Visible", "Hidden", "VeryHidden"]);
+}
+
+#[test]
+fn tab_color_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/tab_color.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let colors = ["Red", "Theme", "None"]
+        .iter()
+        .map(|name| excel.worksheet_tab_color(name).unwrap().unwrap())
+        .collect::<Vec<_>>();
+    // theme colors are not resolved
+    assert_eq!(colors, [Some("FFFF0000".to_string()), None, None]);
+    assert!(excel.worksheet_tab_color("Missing").is_none());
+}
+
+#[test]
+fn sheets_metadata_ods() {
+    setup();
+
+    let path = format!("{}/tests/sheet_visibility.ods", env!("CARGO_MANIFEST_DIR"));
+    let ods: Ods<_> = open_workbook(&path).unwrap();
+    let sheets = ods.sheets_metadata();
+    assert_eq!(
+        sheets
+            .iter()
+            .map(|s| (&*s.name, s.visible))
+            .collect::<Vec<_>>(),
+        [
+            ("Visible", SheetVisible::Visible),
+            ("Hidden", SheetVisible::Hidden)
+        ]
+    );
+}
+
+#[test]
+fn sheets_metadata_visible() {
+    setup();
+
+    for path in &["issues.xls", "issues.xlsb", "issues.ods"] {
+        let path = format!("{}/tests/{}", env!("CARGO_MANIFEST_DIR"), path);
+        let workbook = open_workbook_auto(&path).unwrap();
+        let names = workbook
+            .sheets_metadata()
+            .iter()
+            .map(|s| s.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, workbook.sheet_names());
+        assert!(workbook
+            .sheets_metadata()
+            .iter()
+            .all(|s| s.visible == SheetVisible::Visible));
+    }
+}
+
+#[test]
+fn try_worksheet_range_not_found() {
+    setup();
+
+    for path in &["issues.xls", "issues.xlsx", "issues.xlsb", "issues.ods"] {
+        let path = format!("{}/tests/{}", env!("CARGO_MANIFEST_DIR"), path);
+        let mut workbook = open_workbook_auto(&path).unwrap();
+        let first = workbook.sheet_names()[0].clone();
+        assert_eq!(
+            workbook.try_worksheet_range(&first).unwrap(),
+            workbook.worksheet_range(&first).unwrap().unwrap()
+        );
+        match workbook.try_worksheet_range("NotASheet") {
+            Err(calamine::Error::SheetNotFound {
+                requested,
+                available,
+            }) => {
+                assert_eq!(requested, "NotASheet");
+                assert_eq!(available, workbook.sheet_names());
+            }
+            r => panic!("expecting SheetNotFound error, got {:?}", r),
+        }
+    }
+}
+
+#[test]
+fn encrypted_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/encrypted.xlsx", env!("CARGO_MANIFEST_DIR"));
+    match open_workbook::<Xlsx<_>, _>(&path) {
+        Err(XlsxError::Encrypted) => (),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("encrypted workbook must not be readable"),
+    }
+
+    // a plain ole container (xls) is not an encrypted package
+    let path = format!("{}/tests/issues.xls", env!("CARGO_MANIFEST_DIR"));
+    match open_workbook::<Xlsx<_>, _>(&path) {
+        Err(XlsxError::Zip(_)) => (),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("xls workbook must not be readable as xlsx"),
+    }
+}
+
+#[test]
+fn truncated_xlsx() {
+    setup();
+
+    // first half of issues.xlsx
+    let path = format!("{}/tests/truncated.xlsx", env!("CARGO_MANIFEST_DIR"));
+    match open_workbook::<Xlsx<_>, _>(&path) {
+        Err(XlsxError::Corrupted(e)) => assert_eq!(e, "truncated archive"),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("truncated workbook must not be readable"),
+    }
+
+    // valid central directory, but broken entry
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut data = std::fs::read(&path).unwrap();
+    data[..4].copy_from_slice(b"PK\0\0");
+    match Xlsx::new(Cursor::new(data)) {
+        Err(XlsxError::Corrupted(_)) => (),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("corrupted workbook must not be readable"),
+    }
+}
+
+#[test]
+fn range_from_iter() {
+    let range: Range<DataType> = vec![
+        Cell::new((3, 0), Float(3.)),
+        Cell::new((1, 2), String("a".to_string())),
+        Cell::new((2, 1), Bool(true)),
+        Cell::new((2, 1), Bool(false)),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(range.start(), Some((1, 0)));
+    assert_eq!(range.end(), Some((3, 2)));
+    range_eq!(
+        range,
+        [
+            [Empty, Empty, String("a".to_string())],
+            [Empty, Bool(false), Empty],
+            [Float(3.), Empty, Empty]
+        ]
+    );
+
+    let range: Range<DataType> = Vec::new().into_iter().collect();
+    assert!(range.is_empty());
+}
+
+#[test]
+fn range_from_vec() {
+    let data = vec![
+        Float(1.),
+        Empty,
+        Bool(true),
+        String("a".to_string()),
+        Empty,
+        Empty,
+    ];
+    let range = Range::from_vec((2, 1), 3, data).unwrap();
+    assert_eq!(range.start(), Some((2, 1)));
+    assert_eq!(range.end(), Some((3, 3)));
+    range_eq!(
+        range,
+        [
+            [Float(1.), Empty, Bool(true)],
+            [String("a".to_string()), Empty, Empty]
+        ]
+    );
+
+    assert!(Range::<DataType>::from_vec((0, 0), 3, Vec::new())
+        .unwrap()
+        .is_empty());
+    assert!(Range::from_vec((0, 0), 4, vec![Empty; 6]).is_err());
+    assert!(Range::from_vec((0, 0), 0, vec![Empty; 6]).is_err());
+    assert!(Range::from_vec((0, std::u32::MAX), 2, vec![Empty; 2]).is_err());
+}
+
+#[test]
+fn date_iso_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/date_iso.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [DateTime(42515.), DateTime(42515.)],
+            [DateTime(42515.75), String("garbage".to_string())]
+        ]
+    );
+
+    // same dates, in the 1904 date system of the workbook
+    let path = format!("{}/tests/date_iso_1904.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.date_system(), DateSystem::V1904);
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [DateTime(41053.), DateTime(41053.)],
+            [DateTime(41053.75), String("garbage".to_string())]
+        ]
+    );
+}
+
+#[test]
+fn range_rows_rev_len() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    assert_eq!(range.rows().len(), 3);
+    let last = range.rows().next_back().unwrap();
+    assert_eq!(last, [Float(3.), String("c".to_string())]);
+    let firsts = range.rows().rev().map(|r| r[0].clone()).collect::<Vec<_>>();
+    assert_eq!(firsts, [Float(3.), Float(2.), Float(1.)]);
+
+    let mut rows = range.rows();
+    rows.next();
+    assert_eq!(rows.len(), 2);
+
+    let empty: Range<DataType> = Range::empty();
+    assert_eq!(empty.rows().len(), 0);
+    assert!(empty.rows().next_back().is_none());
+}
+
+#[test]
+fn worksheet_range_lenient_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/invalid_cells.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    assert!(excel.worksheet_range("Sheet1").unwrap().is_err());
+
+    let (range, errors) = excel.worksheet_range_lenient("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [Float(1.), Empty, String("a".to_string())],
+            [Empty, String("#BAD!".to_string()), Bool(true)]
+        ]
+    );
+    let positions = errors.iter().map(|(pos, _)| *pos).collect::<Vec<_>>();
+    assert_eq!(positions, [(0, 1), (1, 0)]);
+    match errors[0].1 {
+        XlsxError::ParseFloat(_) => (),
+        ref e => panic!("unexpected error {}", e),
+    }
+    assert!(excel.worksheet_range_lenient("NotASheet").is_none());
+}
+
+#[test]
+fn no_shared_strings_xlsx() {
+    setup();
+
+    // inline strings only, no sharedStrings.xml part but a dangling shared string index
+    let path = format!(
+        "{}/tests/no_shared_strings.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    match excel.worksheet_range("Sheet1").unwrap() {
+        Err(XlsxError::Unexpected(_)) => (),
+        r => panic!("unexpected {:?}", r),
+    }
+    let (range, errors) = excel.worksheet_range_lenient("Sheet1").unwrap().unwrap();
+    range_eq!(range, [[String("inline".to_string())]]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, (0, 1));
+}
+
+#[test]
+fn xlsx_options() {
+    setup();
+
+    let path = format!("{}/tests/invalid_cells.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel = Xlsx::open_with_options(&path, XlsxOptions::new().lenient(true)).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [Float(1.), Empty, String("a".to_string())],
+            [Empty, String("#BAD!".to_string()), Bool(true)]
+        ]
+    );
+    assert_eq!(excel.worksheets().len(), 1);
+
+    let path = format!("{}/tests/date.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().convert_dates(false)).unwrap();
+    let range = excel.worksheet_range_at(0).unwrap().unwrap();
+    assert_eq!(range.get_value((0, 0)), Some(&Float(44197.)));
+
+    // shared strings are only read with the first worksheet values
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().lazy_shared_strings(true)).unwrap();
+    assert!(excel.sheet_names().contains(&"issue2".to_string()));
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    assert_eq!(range.get_value((2, 1)), Some(&String("c".to_string())));
+    let mut excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().lazy_shared_strings(true)).unwrap();
+    let range = excel.worksheet_range_ref("issue2").unwrap().unwrap();
+    // lazy shared strings are decoded into owned strings
+    assert_eq!(
+        range.get_value((0, 1)),
+        Some(&DataTypeRef::String("a".to_string()))
+    );
+
+    // shared strings table can be inspected directly
+    let mut excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().lazy_shared_strings(true)).unwrap();
+    assert_eq!(excel.shared_string_count().unwrap(), 12);
+    assert_eq!(excel.shared_string(0).unwrap(), Some("a"));
+    assert_eq!(excel.shared_string(4).unwrap(), Some("&"));
+    assert_eq!(excel.shared_string(11).unwrap(), Some("àâéêèçöïî«»"));
+    assert_eq!(excel.shared_string(12).unwrap(), None);
+
+    // lazy shared strings don't change the values read
+    let lazy = XlsxOptions::new().lazy_shared_strings(true).clone();
+    for name in &[
+        "issues",
+        "richtext-namespaced",
+        "rph",
+        "empty_strings",
+        "prefixed",
+        "shift_jis",
+        "no_shared_strings",
+    ] {
+        let path = format!("{}/tests/{}.xlsx", env!("CARGO_MANIFEST_DIR"), name);
+        let mut eager: Xlsx<_> = open_workbook(&path).unwrap();
+        let mut excel = Xlsx::open_with_options(&path, &lazy).unwrap();
+        assert_eq!(
+            excel.shared_string_count().unwrap(),
+            eager.shared_string_count().unwrap(),
+            "{}",
+            name
+        );
+        for (sheet, range) in eager.worksheets() {
+            let lazy_range = excel.worksheet_range(&sheet).unwrap().unwrap();
+            assert_eq!(lazy_range, range, "{} {}", name, sheet);
+        }
+    }
+
+    // buffer capacity doesn't change the values read
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut default: Xlsx<_> = open_workbook(&path).unwrap();
+    for &capacity in &[1, 64 * 1024] {
+        let mut excel =
+            Xlsx::open_with_options(&path, XlsxOptions::new().buffer_capacity(capacity)).unwrap();
+        assert_eq!(
+            excel.worksheet_range("issue2").unwrap().unwrap(),
+            default.worksheet_range("issue2").unwrap().unwrap()
+        );
+    }
+
+    // cached ranges are the same as freshly parsed ones
+    let mut excel = Xlsx::open_with_options(&path, XlsxOptions::new().cache_ranges(true)).unwrap();
+    for name in excel.sheet_names().to_owned() {
+        let expected = default.worksheet_range(&name).unwrap().unwrap();
+        assert_eq!(excel.worksheet_range(&name).unwrap().unwrap(), expected);
+        assert_eq!(excel.worksheet_range(&name).unwrap().unwrap(), expected);
+    }
+    excel.invalidate_range_cache();
+    assert_eq!(
+        excel.worksheet_range("issue2").unwrap().unwrap(),
+        default.worksheet_range("issue2").unwrap().unwrap()
+    );
+    assert!(excel.worksheet_range("not a sheet").is_none());
+}
+
+#[test]
+fn ignore_sheet_case_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/temperature.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut default: Xlsx<_> = open_workbook(&path).unwrap();
+    let expected = default.worksheet_range("Sheet1").unwrap().unwrap();
+    assert!(default.worksheet_range("sheet1").is_none());
+
+    let mut excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().ignore_sheet_case(true)).unwrap();
+    assert_eq!(excel.worksheet_range("sheet1").unwrap().unwrap(), expected);
+    assert_eq!(excel.worksheet_range("SHEET1").unwrap().unwrap(), expected);
+    assert!(excel.worksheet_merge_cells("sheet1").is_some());
+    assert!(excel.worksheet_range("Sheet2").is_none());
+    match excel.try_worksheet_range("Sheet2") {
+        Err(calamine::Error::SheetNotFound {
+            requested,
+            available,
+        }) => {
+            assert_eq!(requested, "Sheet2");
+            assert_eq!(available, ["Sheet1"]);
+        }
+        r => panic!("expecting SheetNotFound error, got {:?}", r),
+    }
+}
+
+#[test]
+fn self_closing_cells_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/empty_cells.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.start(), Some((0, 1)));
+    range_eq!(
+        range,
+        [
+            [Float(1.), Empty, String("a".to_string())],
+            [Empty, Bool(false), Empty]
+        ]
+    );
+}
+
+#[test]
+fn number_formats_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/number_formats.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let formats = excel.worksheet_number_formats("Sheet1").unwrap().unwrap();
+    range_eq!(
+        formats,
+        [
+            ["General", "0.00%", "#,##0"],
+            ["0.0%", "", "General"] // B2 has no value, C2 has a locale dependent format
+        ]
+    );
+
+    let path = format!("{}/tests/date.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let formats = excel.worksheet_number_formats("Sheet1").unwrap().unwrap();
+    assert_eq!(formats.get_value((0, 0)).unwrap(), "yyyy\\-mm\\-dd");
+    assert!(excel.worksheet_number_formats("NotASheet").is_none());
+}
+
+#[test]
+fn all_ranges() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel = open_workbook_auto(&path).unwrap();
+    let ranges = excel.all_ranges().unwrap();
+    let worksheets = excel.worksheets();
+    assert_eq!(ranges.len(), worksheets.len());
+    for (name, range) in worksheets {
+        assert_eq!(ranges[&name].to_vec(), range.to_vec());
+    }
+
+    let path = format!("{}/tests/sheet_visibility.ods", env!("CARGO_MANIFEST_DIR"));
+    let mut excel = open_workbook_auto(&path).unwrap();
+    let ranges = excel.all_ranges().unwrap();
+    assert_eq!(ranges.keys().collect::<Vec<_>>(), ["Visible"]);
+}
+
+#[test]
+fn duration_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/duration.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [[Duration(1.5), Duration(0.25), DateTime(44197.), Float(2.)]]
+    );
+
+    #[cfg(feature = "dates")]
+    assert_eq!(
+        range[(0, 0)].as_duration(),
+        Some(chrono::Duration::hours(36))
+    );
+
+    let mut excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().convert_dates(false)).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range[(0, 0)], Float(1.5));
+}
+
+#[test]
+fn pane_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/pane.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let pane = excel.worksheet_pane("Frozen").unwrap().unwrap().unwrap();
+    assert_eq!(
+        pane,
+        Pane {
+            x_split: 1.,
+            y_split: 1.,
+            top_left_cell: Some((1, 1)),
+            state: PaneState::Frozen,
+        }
+    );
+    let pane = excel.worksheet_pane("Split").unwrap().unwrap().unwrap();
+    assert_eq!(pane.x_split, 2400.);
+    assert_eq!(pane.y_split, 1200.);
+    assert_eq!(pane.top_left_cell, Some((4, 2)));
+    assert_eq!(pane.state, PaneState::Split);
+    assert_eq!(excel.worksheet_pane("NoPane").unwrap().unwrap(), None);
+    assert!(excel.worksheet_pane("NotASheet").is_none());
+
+    // the pane doesn't change the data
+    let range = excel.worksheet_range("Frozen").unwrap().unwrap();
+    range_eq!(range, [[Float(1.)]]);
+}
+
+#[test]
+fn conditional_formats_xlsx() {
+    setup();
+
+    let path = format!(
+        "{}/tests/conditional_formatting.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let formats = excel
+        .worksheet_conditional_formats("Sheet1")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        formats,
+        vec![
+            ConditionalFormat {
+                ranges: vec![Dimensions::new((0, 0), (9, 0))],
+                rules: vec![ConditionalFormatRule {
+                    typ: "cellIs".to_string(),
+                    operator: Some("greaterThan".to_string()),
+                    priority: Some(2),
+                    formulas: vec!["5".to_string()],
+                }],
+            },
+            ConditionalFormat {
+                ranges: vec![
+                    Dimensions::new((0, 1), (4, 1)),
+                    Dimensions::new((2, 3), (2, 3))
+                ],
+                rules: vec![
+                    ConditionalFormatRule {
+                        typ: "cellIs".to_string(),
+                        operator: Some("between".to_string()),
+                        priority: Some(1),
+                        formulas: vec!["2".to_string(), "$A$1&\"x\"".to_string()],
+                    },
+                    ConditionalFormatRule {
+                        typ: "expression".to_string(),
+                        operator: None,
+                        priority: Some(3),
+                        formulas: vec!["MOD(ROW(),2)=0".to_string()],
+                    }
+                ],
+            },
+            // x14 extension
+            ConditionalFormat {
+                ranges: vec![Dimensions::new((0, 2), (9, 2))],
+                rules: vec![ConditionalFormatRule {
+                    typ: "dataBar".to_string(),
+                    operator: None,
+                    priority: None,
+                    formulas: vec![],
+                }],
+            },
+        ]
+    );
+    assert_eq!(
+        excel
+            .worksheet_conditional_formats("Sheet2")
+            .unwrap()
+            .unwrap(),
+        vec![]
+    );
+    assert!(excel.worksheet_conditional_formats("Sheet3").is_none());
+
+    // conditional formats don't change the data
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.get_size(), (10, 2));
+}
+
+#[test]
+fn data_validations_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/data_validation.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let validations = excel.worksheet_data_validations("Sheet1").unwrap().unwrap();
+    assert_eq!(
+        validations,
+        vec![
+            DataValidation {
+                ranges: vec![Dimensions::new((1, 1), (9, 1))],
+                typ: "list".to_string(),
+                operator: None,
+                formula1: Some("\"Yes,No\"".to_string()),
+                formula2: None,
+            },
+            DataValidation {
+                ranges: vec![
+                    Dimensions::new((1, 2), (9, 2)),
+                    Dimensions::new((0, 4), (0, 4))
+                ],
+                typ: "whole".to_string(),
+                operator: Some("between".to_string()),
+                formula1: Some("1".to_string()),
+                formula2: Some("10".to_string()),
+            },
+            DataValidation {
+                ranges: vec![Dimensions::new((1, 3), (1, 3))],
+                typ: "decimal".to_string(),
+                operator: Some("greaterThan".to_string()),
+                formula1: Some("$A$1<0".to_string()),
+                formula2: None,
+            },
+            // x14 extension
+            DataValidation {
+                ranges: vec![Dimensions::new((1, 5), (4, 5))],
+                typ: "list".to_string(),
+                operator: None,
+                formula1: Some("Lists!$A$1:$A$3".to_string()),
+                formula2: None,
+            },
+        ]
+    );
+    assert_eq!(
+        excel.worksheet_data_validations("Lists").unwrap().unwrap(),
+        vec![]
+    );
+    assert!(excel.worksheet_data_validations("Sheet3").is_none());
+}
+
+#[test]
+fn pivot_caches_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/pivot_cache.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    assert_eq!(
+        excel.pivot_caches().unwrap(),
+        vec![
+            PivotCacheInfo {
+                source_type: "worksheet".to_string(),
+                sheet: Some("Sheet1".to_string()),
+                range: Some(Dimensions::new((0, 0), (2, 1))),
+                name: None,
+                fields: vec!["label".to_string(), "value".to_string()],
+            },
+            // named source
+            PivotCacheInfo {
+                source_type: "worksheet".to_string(),
+                sheet: None,
+                range: None,
+                name: Some("Temperatures".to_string()),
+                fields: vec!["value".to_string()],
+            },
+        ]
+    );
+
+    let path = format!("{}/tests/temperature.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.pivot_caches().unwrap(), vec![]);
+}
+
+#[test]
+fn date_system_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/date_1904.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.date_system(), DateSystem::V1904);
+    // serial numbers are returned as is
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.get_value((0, 0)), Some(&DateTime(42515.)));
+
+    let path = format!("{}/tests/date.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.date_system(), DateSystem::V1900);
+
+    let excel =
+        Xlsx::open_with_options(&path, XlsxOptions::new().date_system(DateSystem::V1904)).unwrap();
+    assert_eq!(excel.date_system(), DateSystem::V1904);
+}
+
+#[test]
+fn read_part_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let content_types = excel.read_part("[Content_Types].xml").unwrap().unwrap();
+    let content_types = std::str::from_utf8(&content_types).unwrap();
+    assert!(content_types.contains("/xl/workbook.xml"));
+    assert!(excel.read_part("customXml/item1.xml").is_none());
+}
+
+#[test]
+fn properties_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let properties = excel.properties().unwrap();
+    assert_eq!(
+        properties.creator.as_deref(),
+        Some("Johann Tuffe (jtuffe010814)")
+    );
+    assert_eq!(properties.last_modified_by, properties.creator);
+    assert_eq!(properties.created.as_deref(), Some("2016-10-18T10:19:50Z"));
+    assert_eq!(properties.modified.as_deref(), Some("2017-04-18T09:10:04Z"));
+    assert_eq!(properties.company.as_deref(), Some("SOCIETE GENERALE"));
+    assert_eq!(properties.title, None);
+
+    // no docProps parts
+    let path = format!("{}/tests/merged_range.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.properties().unwrap(), Default::default());
+}
+
+#[test]
+fn wrong_dimension_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/wrong_dimension.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    // declared dimension (A1) is smaller than the actual data (A1:C3)
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.start(), Some((0, 0)));
+    assert_eq!(range.end(), Some((2, 2)));
+    assert_eq!(range.get_size(), (3, 3));
+    assert_eq!(range.cells().count(), 9);
+    assert_eq!(range.get_value((2, 2)), Some(&Float(3.)));
+
+    let range = excel.worksheet_range("Inverted").unwrap().unwrap();
+    range_eq!(range, [[Float(2.)]]);
+}
+
+#[test]
+fn row_spans_xlsx() {
+    setup();
+
+    // row spans disagree with actual cells, cell positions must win
+    let path = format!("{}/tests/row_spans.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.start(), Some((0, 0)));
+    assert_eq!(range.end(), Some((2, 3)));
+    range_eq!(
+        range,
+        [
+            [Float(1.), Empty, Empty, Float(4.)],
+            [Empty, Empty, Empty, Empty],
+            [Empty, Float(2.), Empty, Empty]
+        ]
+    );
+}
+
+#[test]
+fn range_rows_mut() {
+    let mut range: Range<DataType> = Range::new((1, 1), (2, 3));
+    range.set_value((1, 1), String(" a ".to_string()));
+    range.set_value((2, 3), String("b ".to_string()));
+
+    for row in range.rows_mut() {
+        for cell in row.iter_mut() {
+            if let String(s) = cell {
+                *s = s.trim().to_string();
+            }
+        }
+    }
+    assert_eq!(range.rows_mut().len(), 2);
+    range_eq!(
+        range,
+        [
+            [String("a".to_string()), Empty, Empty],
+            [Empty, Empty, String("b".to_string())]
+        ]
+    );
+
+    *range.get_value_mut((2, 2)).unwrap() = DataType::Int(1);
+    assert_eq!(range[(1, 1)], DataType::Int(1));
+    assert!(range.get_value_mut((0, 1)).is_none());
+    assert!(range.get_value_mut((1, 4)).is_none());
+
+    let mut empty: Range<DataType> = Range::empty();
+    assert!(empty.rows_mut().next().is_none());
+}
+
+#[test]
+fn write_csv_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/formulas.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+
+    let mut csv = Vec::new();
+    range.write_csv(&mut csv).unwrap();
+    assert_eq!(csv, b"1,2\r\nx,xy\r\n");
+
+    let mut csv = Vec::new();
+    Range::<DataType>::empty().write_csv(&mut csv).unwrap();
+    assert!(csv.is_empty());
+}
+
+#[test]
+fn hyperlinks_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/hyperlinks.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let links = excel.worksheet_hyperlinks("Sheet1").unwrap().unwrap();
+    assert_eq!(
+        links,
+        vec![
+            ((1, 1), "https://example.com/".to_string()),
+            ((2, 0), "Sheet2!A1".to_string()),
+            ((3, 2), "https://example.com/doc.html#part".to_string()),
+        ]
+    );
+    // no relationships part
+    assert!(excel
+        .worksheet_hyperlinks("Sheet2")
+        .unwrap()
+        .unwrap()
+        .is_empty());
+    assert!(excel.worksheet_hyperlinks("Sheet3").is_none());
+}
+
+#[test]
+fn calc_properties_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/calc_properties.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let calc = excel.calc_properties();
+    assert_eq!(calc.mode, CalcMode::Manual);
+    assert!(calc.full_calc_on_load);
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.calc_properties(), CalcProperties::default());
+}
+
+#[test]
+fn xml_encoding_xlsx() {
+    setup();
+
+    // parts declared (and encoded) as Shift_JIS
+    let path = format!("{}/tests/shift_jis.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [[
+            String("日本語".to_string()),
+            String("テスト".to_string()),
+            String("ｶﾀｶﾅ".to_string())
+        ]]
+    );
+
+    // UTF-16 shared strings cannot be read
+    let path = format!(
+        "{}/tests/utf16_shared_strings.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    match open_workbook::<Xlsx<_>, _>(&path) {
+        Err(XlsxError::UnsupportedEncoding(p)) => assert_eq!(p, "xl/sharedStrings.xml"),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("utf-16 part should not be read"),
+    }
+}
+
+#[test]
+fn xlsx_from_archive() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let data = std::fs::read(&path).unwrap();
+    let zip = zip::ZipArchive::new(Cursor::new(data)).unwrap();
+    let mut excel = Xlsx::from_archive(zip).unwrap();
+    let mut expected: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.sheet_names(), expected.sheet_names());
+    assert_eq!(
+        excel.worksheet_range("issue2").unwrap().unwrap(),
+        expected.worksheet_range("issue2").unwrap().unwrap()
+    );
+}
+
+#[test]
+fn sheet_format_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/sheet_format.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let format = excel.worksheet_format("Sheet1").unwrap().unwrap();
+    assert_eq!(format.default_row_height, Some(15.));
+    assert_eq!(format.default_column_width, None);
+    assert_eq!(
+        format.column_widths.into_iter().collect::<Vec<_>>(),
+        vec![(1, 12.5), (3, 3.), (4, 3.), (5, 3.)]
+    );
+    assert_eq!(
+        format.row_heights.into_iter().collect::<Vec<_>>(),
+        vec![(2, 30.), (3, 7.5)]
+    );
+    assert_eq!(
+        format.hidden_columns.into_iter().collect::<Vec<_>>(),
+        vec![7, 8]
+    );
+    assert_eq!(
+        format.column_outline_levels.into_iter().collect::<Vec<_>>(),
+        vec![(7, 1), (8, 1)]
+    );
+    assert_eq!(
+        format.hidden_rows.into_iter().collect::<Vec<_>>(),
+        vec![4, 5]
+    );
+    assert_eq!(
+        format.row_outline_levels.into_iter().collect::<Vec<_>>(),
+        vec![(4, 2), (5, 1)]
+    );
+
+    assert_eq!(
+        excel.worksheet_format("Sheet2").unwrap().unwrap(),
+        SheetFormat::default()
+    );
+    assert!(excel.worksheet_format("Sheet3").is_none());
+}
+
+#[test]
+fn error_cells_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/error_cells.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [Float(1.), Error(Ref), Float(2.)],
+            [Error(NA), String("#SPILL!".to_string()), Float(3.)]
+        ]
+    );
+}
+
+#[test]
+fn non_finite_numbers_xlsx() {
+    setup();
+
+    // every string below is accepted by `f64::from_str`
+    let path = format!("{}/tests/non_finite.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.get_size(), (2, 11));
+    for c in 0..11 {
+        assert_eq!(range.get((0, c)), Some(&Error(Num)), "column {}", c);
+    }
+    assert_eq!(range.get((1, 0)), Some(&Error(Num)));
+    assert_eq!(range.get((1, 1)), Some(&String("NaN".to_string())));
+    assert_eq!(range.get((1, 2)), Some(&Float(1.5)));
+}
+
+#[test]
+fn active_sheet_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.active_sheet(), Some("Sheet1"));
+
+    let path = format!("{}/tests/issue127.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.active_sheet(), Some("Sheet8"));
+
+    // no activeTab
+    let path = format!("{}/tests/errors.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.active_sheet(), None);
+
+    // invalid activeTab is ignored
+    let path = format!(
+        "{}/tests/invalid_active_tab.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.active_sheet(), None);
+}
+
+#[test]
+fn worksheet_range_str_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let full = excel.worksheet_range("issue2").unwrap().unwrap();
+
+    let block = excel.worksheet_range_str("issue2!A1:B3").unwrap().unwrap();
+    assert_eq!(block.start(), Some((0, 0)));
+    assert_eq!(block.end(), Some((2, 1)));
+    for &pos in &[(0, 1), (1, 1), (2, 1)] {
+        assert_eq!(block.get_value(pos), full.get_value(pos));
+    }
+    // quoted sheet name, absolute markers, inverted corners, lower case
+    let same = excel
+        .worksheet_range_str("'issue2'!$b$3:a1")
+        .unwrap()
+        .unwrap();
+    assert_eq!(same, block);
+    // single cell
+    let cell = excel.worksheet_range_str("issue2!B3").unwrap().unwrap();
+    range_eq!(cell, [[String("c".to_string())]]);
+    // block outside of the used cells
+    let empty = excel
+        .worksheet_range_str("issue2!Z10:Z11")
+        .unwrap()
+        .unwrap();
+    assert_eq!(empty.get_size(), (2, 1));
+    assert_eq!(empty.used_cells().count(), 0);
+    // whole columns and whole rows stop at the end of the used cells
+    let column = excel.worksheet_range_str("issue2!$B:$B").unwrap().unwrap();
+    assert_eq!(column, full.range((0, 1), (full.end().unwrap().0, 1)));
+    let rows = excel.worksheet_range_str("issue2!2:3").unwrap().unwrap();
+    assert_eq!(rows, full.range((1, 0), (2, full.end().unwrap().1)));
+
+    // defaults to the active sheet
+    assert_eq!(excel.active_sheet(), Some("Sheet1"));
+    let block = excel.worksheet_range_str("A1:A2").unwrap().unwrap();
+    let sheet1 = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(block, sheet1.range((0, 0), (1, 0)));
+
+    assert!(excel.worksheet_range_str("missing!A1").is_none());
+    assert!(excel.worksheet_range_str("issue2!A1:B").unwrap().is_err());
+}
+
+#[test]
+fn chartsheet_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/chartsheet.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let types = excel
+        .sheets_metadata()
+        .iter()
+        .map(|s| (&*s.name, s.typ))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            ("Data", SheetType::Worksheet),
+            ("Chart1", SheetType::ChartSheet),
+            ("Macro1", SheetType::MacroSheet)
+        ]
+    );
+
+    match excel.worksheet_range("Chart1") {
+        Some(Err(XlsxError::NotAWorksheet(typ))) => assert_eq!(typ, "chartsheet"),
+        r => panic!("expecting NotAWorksheet error, got {:?}", r),
+    }
+    assert!(excel.worksheet_formula("Chart1").unwrap().is_err());
+    let range = excel.worksheet_range("Macro1").unwrap().unwrap();
+    range_eq!(range, [[String("1".to_string())]]);
+
+    // sheets without cells are skipped
+    let names = excel
+        .worksheets()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Data", "Macro1"]);
+}
+
+#[test]
+fn worksheet_range_with_progress_xlsx() {
+    setup();
+
+    // 2499 rows with values, row 5 is missing
+    let path = format!("{}/tests/many_rows.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let mut calls = Vec::new();
+    let range = excel
+        .worksheet_range_with_progress("Sheet1", |rows| calls.push(rows))
+        .unwrap()
+        .unwrap();
+    assert_eq!(range, excel.worksheet_range("Sheet1").unwrap().unwrap());
+    let rows = range
+        .rows()
+        .filter(|r| r.iter().any(|c| !c.is_empty()))
+        .count();
+    let expected = (1..=rows / 1000)
+        .map(|i| i * 1000)
+        .chain(Some(rows))
+        .collect::<Vec<_>>();
+    assert_eq!(calls, expected);
+    assert!(calls.len() > 1, "{:?}", calls);
+
+    let mut calls = Vec::new();
+    assert!(excel
+        .worksheet_range_with_progress("missing", |rows| calls.push(rows))
+        .is_none());
+    assert!(calls.is_empty());
+}
+
+#[test]
+fn worksheet_head_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/many_rows.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    let head = excel.worksheet_head("Sheet1", 10).unwrap().unwrap();
+    // row 5 is not written, it is not counted
+    assert_eq!(head.get_size(), (11, 1));
+    assert_eq!(head, range.range((0, 0), (10, 0)));
+
+    // more rows than the worksheet
+    let head = excel
+        .worksheet_head("Sheet1", std::usize::MAX)
+        .unwrap()
+        .unwrap();
+    assert_eq!(head, range);
+    assert!(excel
+        .worksheet_head("Sheet1", 0)
+        .unwrap()
+        .unwrap()
+        .is_empty());
+    assert!(excel.worksheet_head("missing", 10).is_none());
+}
+
+#[test]
+fn shared_strings_count_xlsx() {
+    setup();
+
+    // declares 3 unique strings but has 4, 2 of them without text
+    let path = format!(
+        "{}/tests/shared_strings_count.xlsx",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert_eq!(excel.shared_string_count().unwrap(), 4);
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [[
+            String("a".to_string()),
+            String("".to_string()),
+            String("".to_string()),
+            String("d".to_string())
+        ]]
+    );
+}
+
+#[test]
+fn empty_strings_xlsx() {
+    setup();
+
+    // explicit empty strings (shared, inline or formula result) are not empty cells
+    let path = format!("{}/tests/empty_strings.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    range_eq!(
+        range,
+        [
+            [
+                String("".to_string()),
+                String("".to_string()),
+                String("".to_string()),
+                Empty,
+                String("x".to_string())
+            ],
+            [Empty, Empty, String(" ".to_string()), Empty, Empty]
+        ]
+    );
+    let range = excel.worksheet_range_ref("Sheet1").unwrap().unwrap();
+    assert_eq!(
+        range.get_value((0, 0)),
+        Some(&DataTypeRef::SharedString(""))
+    );
+    assert_eq!(range.get_value((0, 3)), Some(&DataTypeRef::Empty));
+}
+
+#[test]
+fn comments_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/comments.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let comments = excel.worksheet_comments("Sheet1").unwrap().unwrap();
+    assert_eq!(
+        comments,
+        vec![
+            ((0, 1), "check this value".to_string()),
+            ((2, 2), "Reviewer:\nwrong total".to_string()),
+        ]
+    );
+    assert!(excel
+        .worksheet_comments("Sheet2")
+        .unwrap()
+        .unwrap()
+        .is_empty());
+    assert!(excel.worksheet_comments("Sheet3").is_none());
+}
+
+#[test]
+fn worksheet_range_filtered_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel
+        .worksheet_range_filtered("issue2", |row| row[1] == *"b")
+        .unwrap()
+        .unwrap();
+    assert_eq!(range.start(), Some((1, 0)));
+    assert_eq!(range.end(), Some((1, 1)));
+    range_eq!(range, [[Float(2.), String("b".to_string())]]);
+
+    let range = excel
+        .worksheet_range_filtered("issue2", |_| false)
+        .unwrap()
+        .unwrap();
+    assert!(range.is_empty());
+    assert!(excel
+        .worksheet_range_filtered("missing", |_| true)
+        .is_none());
+
+    // rows are indexed from column A, even when they start later
+    let path = format!("{}/tests/no_r_attribute.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let mut widths = Vec::new();
+    let range = excel
+        .worksheet_range_filtered("Sheet1", |row| {
+            widths.push(row.len());
+            row[0].is_empty()
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(widths, vec![2, 4, 1, 3]);
+    range_eq!(range, [[Float(3.), Float(4.)]]);
+    assert_eq!(range.start(), Some((1, 2)));
+}
+
+#[test]
+fn no_r_attribute_xlsx() {
+    setup();
+
+    // rows and cells without 'r' attribute follow the previous ones
+    let path = format!("{}/tests/no_r_attribute.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    assert_eq!(range.start(), Some((0, 0)));
+    assert_eq!(range.end(), Some((5, 3)));
+    range_eq!(
+        range,
+        [
+            [Float(1.), Float(2.), Empty, Empty],
+            [Empty, Empty, Float(3.), Float(4.)],
+            [Empty, Empty, Empty, Empty],
+            [Empty, Empty, Empty, Empty],
+            [Float(5.), Empty, Empty, Empty],
+            [String("a".to_string()), Empty, Float(6.), Empty]
+        ]
+    );
+}
+
+#[test]
+fn range_partial_eq() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let a = excel.worksheet_range("issue2").unwrap().unwrap();
+    let b = excel.worksheet_range("issue2").unwrap().unwrap();
+    assert_eq!(a, b);
+
+    let mut c = b.clone();
+    c.set_value((0, 0), Bool(true));
+    assert_ne!(a, c);
+
+    // same cells at another position
+    let mut d = Range::new((1, 1), (1, 1));
+    d.set_value((1, 1), Float(1.));
+    let mut e = Range::new((0, 0), (0, 0));
+    e.set_value((0, 0), Float(1.));
+    assert_ne!(d, e);
+    assert_eq!(d, d.clone());
+}
+
+#[test]
+fn range_cell_iter() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    let cells = range.cell_iter().collect::<Vec<_>>();
+    assert_eq!(cells.len(), 6);
+    assert_eq!(cells[0], (0, 0, &Float(1.)));
+    assert_eq!(cells[5], (2, 1, &String("c".to_string())));
+    for (row, col, v) in range.cell_iter() {
+        assert_eq!(range.get_value((row, col)), Some(v));
+    }
+
+    // absolute positions
+    let mut range = Range::new((3, 2), (4, 3));
+    range.set_value((4, 2), Bool(true));
+    assert_eq!(range.cell_iter().len(), 4);
+    assert_eq!(range.cell_iter().nth(2), Some((4, 2, &Bool(true))));
+    assert_eq!(range.cell_iter().next_back(), Some((4, 3, &Empty)));
+    assert_eq!(Range::<DataType>::empty().cell_iter().next(), None);
+}
+
+#[test]
+fn range_find() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    let c = String("c".to_string());
+    let (row, col) = range.find(|v| v == &c).unwrap();
+    assert_eq!(range.get((row, col)), Some(&c));
+    assert_eq!(range.find(|v| v == &String("missing".to_string())), None);
+
+    let mut range: Range<DataType> = Range::new((1, 1), (3, 2));
+    range.set_value((1, 2), Float(1.));
+    range.set_value((2, 1), Float(2.));
+    range.set_value((3, 2), Float(3.));
+    let is_float = |v: &DataType| v.get_float().is_some();
+    assert_eq!(range.find(is_float), Some((0, 1)));
+    assert_eq!(
+        range.find_all(is_float).collect::<Vec<_>>(),
+        vec![(0, 1), (1, 0), (2, 1)]
+    );
+    assert_eq!(range.find_all(is_float).next_back(), Some((2, 1)));
+    assert_eq!(range.find_all(|v| v.is_empty()).count(), 3);
+    assert_eq!(Range::<DataType>::empty().find(|_| true), None);
+}
+
+#[test]
+fn range_to_strings() {
+    setup();
+
+    let mut range: Range<DataType> = Range::new((0, 0), (1, 3));
+    range.set_value((0, 0), DataType::Int(-3));
+    range.set_value((0, 1), Float(0.25));
+    range.set_value((0, 2), String("verbatim, \"text\"".to_string()));
+    range.set_value((0, 3), Bool(false));
+    range.set_value((1, 0), Error(Div0));
+    range.set_value((1, 2), Bool(true));
+    let text: Vec<Vec<std::string::String>> = range.into();
+    assert_eq!(
+        text,
+        vec![
+            vec!["-3", "0.25", "verbatim, \"text\"", "FALSE"],
+            vec!["#DIV/0!", "", "TRUE", ""],
+        ]
+    );
+
+    let text: Vec<Vec<std::string::String>> = Range::<DataType>::empty().into();
+    assert!(text.is_empty());
+}
+
+#[test]
+fn datatype_coerce_number() {
+    let number = |s: &str| String(s.to_string()).coerce_number();
+    assert_eq!(Float(1.5).coerce_number(), Some(1.5));
+    assert_eq!(DataType::Int(-2).coerce_number(), Some(-2.));
+    assert_eq!(number("42"), Some(42.));
+    assert_eq!(number("  -0.5\t"), Some(-0.5));
+    assert_eq!(number("+1e3"), Some(1000.));
+    assert_eq!(number("1,234"), Some(1234.));
+    assert_eq!(number("-1,234,567.25"), Some(-1_234_567.25));
+    assert_eq!(number("12,34"), None);
+    assert_eq!(number(",123"), None);
+    assert_eq!(number("1,234.5,6"), None);
+    assert_eq!(number("1 234"), None);
+    assert_eq!(number(""), None);
+    assert_eq!(number("abc"), None);
+    assert_eq!(number("inf"), None);
+    assert_eq!(number("NaN"), None);
+    assert_eq!(number("1e400"), None);
+    assert_eq!(Bool(true).coerce_number(), None);
+    assert_eq!(Empty.coerce_number(), None);
+    assert_eq!(DateTime(1.).coerce_number(), None);
+}
+
+#[test]
+fn range_deduplicate_rows() {
+    setup();
+
+    let header = [String("name".to_string()), String("value".to_string())];
+    let mut range: Range<DataType> = Range::new((2, 1), (7, 2));
+    for &row in &[2, 3, 6] {
+        range.set_value((row, 1), header[0].clone());
+        range.set_value((row, 2), header[1].clone());
+    }
+    range.set_value((4, 1), String("a".to_string()));
+    range.set_value((4, 2), Float(1.));
+    range.set_value((5, 1), String("a".to_string()));
+    range.set_value((5, 2), Float(2.));
+    // row 7 is empty
+
+    let deduplicated = range.deduplicate_rows();
+    assert_eq!(deduplicated.start(), Some((2, 1)));
+    assert_eq!(deduplicated.end(), Some((6, 2)));
+    range_eq!(
+        deduplicated,
+        [
+            header.clone(),
+            [String("a".to_string()), Float(1.)],
+            [String("a".to_string()), Float(2.)],
+            header.clone(),
+            [Empty, Empty]
+        ]
+    );
+    // nothing left to remove
+    assert_eq!(deduplicated.deduplicate_rows(), deduplicated);
+
+    let empty_rows: Range<DataType> = Range::new((0, 0), (3, 3));
+    assert_eq!(empty_rows.deduplicate_rows().get_size(), (1, 4));
+    assert!(Range::<DataType>::empty().deduplicate_rows().is_empty());
+}
+
+#[test]
+fn range_trim() {
+    setup();
+
+    let mut range: Range<DataType> = Range::new((0, 0), (4, 4));
+    range.set_value((2, 3), Float(1.));
+    range.set_value((3, 1), String("a".to_string()));
+    let trimmed = range.trim();
+    assert_eq!(trimmed.start(), Some((2, 1)));
+    assert_eq!(trimmed.end(), Some((3, 3)));
+    range_eq!(
+        trimmed,
+        [
+            [Empty, Empty, Float(1.)],
+            [String("a".to_string()), Empty, Empty]
+        ]
+    );
+    // already trimmed
+    assert_eq!(trimmed.trim().to_vec(), trimmed.to_vec());
+
+    assert!(Range::<DataType>::new((2, 2), (3, 3)).trim().is_empty());
+    assert!(Range::<DataType>::empty().trim().is_empty());
+
+    // ranges read from files may be padded with empty cells
+    let path = format!("{}/tests/empty_cells.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let range = excel.worksheet_range("Sheet1").unwrap().unwrap();
+    let trimmed = range.trim();
+    assert_eq!(trimmed.used_cells().count(), range.used_cells().count());
+}
+
+#[test]
+fn range_transpose() {
+    let mut range: Range<DataType> = Range::new((1, 2), (2, 4));
+    range.set_value((1, 2), Float(1.));
+    range.set_value((1, 4), String("a".to_string()));
+    range.set_value((2, 3), Bool(true));
+
+    let transposed = range.transpose();
+    assert_eq!(transposed.start(), Some((2, 1)));
+    assert_eq!(transposed.end(), Some((4, 2)));
+    range_eq!(
+        transposed,
+        [
+            [Float(1.), Empty],
+            [Empty, Bool(true)],
+            [String("a".to_string()), Empty]
+        ]
+    );
+
+    let back = transposed.transpose();
+    assert_eq!(back.start(), range.start());
+    assert_eq!(back.end(), range.end());
+    assert_eq!(back.to_vec(), range.to_vec());
+
+    assert!(Range::<DataType>::empty().transpose().is_empty());
+}
+
+#[test]
+fn serialize_range() {
+    setup();
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let range = excel.worksheet_range("issue2").unwrap().unwrap();
+    assert_eq!(
+        serde_json::to_string(&range).unwrap(),
+        r#"[[1.0,"a"],[2.0,"b"],[3.0,"c"]]"#
+    );
+
+    let range: Range<DataType> = vec![
+        Cell::new((0, 0), Bool(true)),
+        Cell::new((0, 2), Error(NA)),
+        Cell::new((1, 1), DataType::Int(2)),
+    ]
+    .into_iter()
+    .collect();
+    let json = serde_json::to_string(&range).unwrap();
+    assert_eq!(json, r##"[[true,null,"#N/A"],[null,2,null]]"##);
+
+    // round trip, except for errors which are read back as strings
+    let rows: Vec<Vec<DataType>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        rows,
+        [
+            [Bool(true), Empty, String("#N/A".to_string())],
+            [Empty, DataType::Int(2), Empty]
+        ]
+    );
+}
+
+#[test]
+fn bool_text_xlsx() {
+    setup();
+
+    let path = format!("{}/tests/bool_text.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let (range, errors) = excel.worksheet_range_lenient("Sheet1").unwrap().unwrap();
+    assert_eq!(
+        range[0],
+        [
+            Bool(true),
+            Bool(false),
+            Bool(true),
+            Bool(false),
+            Bool(true),
+            Bool(false)
+        ]
+    );
+    assert_eq!(errors.len(), 1);
+    match errors[0] {
+        ((1, 0), XlsxError::ParseBool(_)) => (),
+        ref e => panic!("unexpected error {:?}", e),
+    }
+}
+
+#[test]
+fn vba_project_info() {
+    setup();
+
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let vba = excel.vba_project().unwrap().unwrap();
+    let info = vba.info();
+    assert_eq!(vba.project_name(), "VBAProject");
+    assert_eq!(info.name, "VBAProject");
+    assert_eq!(info.code_page, 1252);
+    assert_eq!(info.doc_string, "");
+    assert_eq!(info.help_file, "");
+    assert_eq!(info.constants, "");
+}
+
+#[test]
+fn vba_project_cached() {
+    setup();
+
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let name = excel
+        .vba_project()
+        .unwrap()
+        .unwrap()
+        .project_name()
+        .to_string();
+    match excel.vba_project().unwrap().unwrap() {
+        Cow::Borrowed(vba) => assert_eq!(vba.project_name(), name),
+        Cow::Owned(_) => panic!("vba project parsed again"),
+    }
+    assert_eq!(excel.reload_vba().unwrap().unwrap().project_name(), name);
+
+    let path = format!("{}/tests/issues.xlsx", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert!(excel.vba_project().is_none());
+}
+
+#[test]
+fn vba_4096_sectors() {
+    setup();
+
+    // same project as vba.xlsm, stored in a version 4 compound file (4096 bytes sectors)
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let v3 = excel.vba_project().unwrap().unwrap().into_owned();
+
+    let path = format!("{}/tests/vba_4096_sectors.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let v4 = excel.vba_project().unwrap().unwrap().into_owned();
+
+    let mut names = v3
+        .get_module_names()
+        .into_iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    let mut v4_names = v4
+        .get_module_names()
+        .into_iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    v4_names.sort();
+    assert_eq!(v4_names, names);
+    for name in names {
+        assert_eq!(v4.get_module(&name).unwrap(), v3.get_module(&name).unwrap());
+    }
+    assert_eq!(v4.vba_version().unwrap(), v3.vba_version().unwrap());
+}
+
+#[test]
+fn vba_version() {
+    setup();
+
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let vba = excel.vba_project().unwrap().unwrap();
+    let (signature, version) = vba.vba_version().unwrap();
+    assert_eq!(signature, 0x61CC);
+    assert_eq!(version, 0x88);
+}
+
+#[test]
+fn vba_stream_names() {
+    setup();
+
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+
+    let vba = excel.vba_project().unwrap().unwrap();
+    let streams = vba.stream_names();
+    for name in &["VBA", "dir", "_VBA_PROJECT", "PROJECT"] {
+        assert!(streams.iter().any(|s| s == name), "missing {}", name);
+    }
+    assert!(!streams.iter().any(|s| s.is_empty() || s == "Root Entry"));
+    for module in vba.get_module_names() {
+        assert!(streams.iter().any(|s| s == module), "missing {}", module);
+    }
+}
+
+#[test]
+fn vba_forms() {
+    setup();
+
+    let path = format!("{}/tests/vba_form.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    let vba = excel.vba_project().unwrap().unwrap();
+    let forms = vba.forms();
+    assert_eq!(forms.len(), 1);
+    assert_eq!(forms[0].name, "UserForm1");
+    let vb_frame = std::str::from_utf8(&forms[0].vb_frame).unwrap();
+    assert!(vb_frame.contains("Caption         =   \"UserForm1\""));
+    assert_eq!(forms[0].f.len(), 32);
+    assert_eq!(forms[0].o, b"Hello from UserForm1");
+    // modules are still read
+    assert!(vba.get_module("testVBA").is_ok());
+
+    let path = format!("{}/tests/vba.xlsm", env!("CARGO_MANIFEST_DIR"));
+    let mut excel: Xlsx<_> = open_workbook(&path).unwrap();
+    assert!(excel.vba_project().unwrap().unwrap().forms().is_empty());
+}